@@ -9,6 +9,9 @@ mod backends;
 pub mod hittable;
 pub use hittable::{ Hittable, HittingInfo };
 
+pub mod texture;
+pub use texture::{ Texture, TexParam };
+
 pub mod material;
 pub use material::{ Material, MatInput, ShadeOutput };
 
@@ -19,7 +22,29 @@ pub mod scene;
 pub use scene::Scene;
 
 pub mod camera;
-pub use camera::Camera;
+pub use camera::{ Camera, CameraSample, RayCollection };
+
+pub mod sampler;
+pub use sampler::Sampler;
+
+pub mod filter;
+pub use filter::Filter;
+
+pub mod tile;
+pub use tile::{ Tile, TileOrder };
+
+pub mod framebuffer;
+pub use framebuffer::FrameBuffer;
+
+pub mod stats;
+pub use stats::RenderStats;
+
+pub mod progress;
+pub use progress::{ Progress, RenderObserver };
 
 pub mod renderer;
-pub use renderer::{ Renderer, RendererConfig, BackendConfig };
\ No newline at end of file
+pub use renderer::{
+    Renderer, RendererConfig, BackendConfig,
+    AdaptiveConfig, ProgressiveConfig, TileConfig,
+    CancelToken, RenderStatus
+};
\ No newline at end of file
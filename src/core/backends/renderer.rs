@@ -1,20 +1,52 @@
-use super::super::{ Scene, Ray, Vec3 };
+use std::{ ops::Range, time::Instant };
+use super::super::{ Scene, RayCollection, FrameBuffer, Tile, RenderStats, CancelToken };
 
 /// Renderer's Render Task
 pub struct RenderTask<'a> {
-    /// The ray set, in which rays' tracing results are sampled into the same pixel.
-    pub rays: Vec<Ray>,
+    /// The tile to be sampled.
+    pub tile: Tile,
+    /// The indices of samples taken in each pixel of tile, in row-major order.
+    /// 
+    /// Samples are splatted around their film positions,
+    /// which may contribute to neighbouring pixels.
+    pub samples: Vec<Range<u32>>,
+    /// Camera ray generator.
+    pub rays: &'a RayCollection,
     /// Rendering scene's ref.
     pub scene: &'a Scene,
-    /// The buffer's index where the rendering result will be put.
-    pub index: usize
+    /// Token which stops the task once triggered.
+    pub cancel: &'a CancelToken,
+    /// Time when the task stops, if rendering is limited in time.
+    pub deadline: Option<Instant>
+}
+
+impl RenderTask<'_> {
+    /// Whether renderer should stop before the next pixel.
+    /// 
+    /// Pixels are sampled as a whole, so their sample counts stay accurate.
+    pub fn interrupted(&self) -> bool {
+        self.cancel.is_cancelled() || self.deadline.is_some_and(|deadline| Instant::now() >= deadline)
+    }
 }
 
 /// Abstraction for Native Renderer
 pub trait NativeRenderer {
     /// Submit a RenderTask to renderer.
+    /// 
+    /// Renderer checks [`RenderTask::interrupted`] before each pixel, and leaves the rest of tile unsampled.
     fn submit(&mut self, task: RenderTask);
 
+    /// Replace renderer's buffer with accumulated one, and continue sampling on it.
+    fn restore(&mut self, framebuffer: FrameBuffer);
+
+    /// Get the statistics accumulated by renderer, whose times are left for the caller.
+    fn stats(&self) -> &RenderStats;
+
+    /// Get the reference of renderer's buffer.
+    fn framebuffer(&self) -> &FrameBuffer;
+
     /// Fetch the buffer from renderer.
-    fn fetch(&self) -> Vec<Vec3>;
-}
\ No newline at end of file
+    fn fetch(&self) -> FrameBuffer {
+        self.framebuffer().clone()
+    }
+}
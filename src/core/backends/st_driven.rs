@@ -1,38 +1,77 @@
 use std::{ rc::Rc, f64::INFINITY };
 use super::super::{ 
-    Ray, Vec3, Interval,
-    Scene, Material, MatInput, HittingInfo
+    Ray, Vec2, Vec3, Interval, FrameBuffer, Filter, Sampler, Tile,
+    Scene, Entity, Material, MatInput, HittingInfo, RenderStats,
+    math::utils::hash
 };
 use super::renderer::{ NativeRenderer, RenderTask };
 
 pub struct STDrivenRendererConfig {
-    /// The size of framebuffer, which generally is the number of pixel.
-    pub fb_size: usize,
+    /// The region of the frame covered by framebuffer, which generally is the whole screen.
+    pub region: Tile,
     /// The maximum number of ray bounce depth.
-    pub max_depth: u32
+    pub max_depth: u32,
+    /// Whether the background is transparent to primary rays.
+    pub transparent_bg: bool,
+    /// Pixel reconstruction filter.
+    pub filter: Rc<dyn Filter>
 }
 
 /// Single-thread CPU Renderer.
 pub struct STDrivenRenderer {
-    framebuffer: Vec<Vec3>,
-    config: STDrivenRendererConfig
+    framebuffer: FrameBuffer,
+    sampler: Box<dyn Sampler>,
+    config: STDrivenRendererConfig,
+    stats: RenderStats,
+    counters: TraceCounters
+}
+
+/// State of the path being traced.
+#[derive(Clone, Copy)]
+struct PathState {
+    /// The remaining number of bounces.
+    depth: u32,
+    /// The wavelength which the path is restricted to, see [`MatInput::wavelength`].
+    wavelength: Option<f64>
+}
+
+/// Counters of tracing a sample, which are folded into [`RenderStats`].
+#[derive(Default)]
+struct TraceCounters {
+    /// Path segments traced.
+    segments: usize,
+    /// Intersection tests of each entity in scene.
+    entity_tests: Vec<u64>
 }
 
 impl STDrivenRenderer {
-    pub fn new(config: STDrivenRendererConfig) -> STDrivenRenderer {
+    /// The distance which scattered rays are pushed off surfaces.
+    const RAY_OFFSET: f64 = 1e-4;
+
+    pub fn new(config: STDrivenRendererConfig, sampler: Box<dyn Sampler>) -> STDrivenRenderer {
         STDrivenRenderer {
-            framebuffer: vec![Vec3::from_scalar(0.0); config.fb_size],
-            config
+            framebuffer: FrameBuffer::with_offset(config.region.size(), config.region.min),
+            sampler,
+            config,
+            stats: RenderStats::default(),
+            counters: TraceCounters::default()
         }
     }
 
-    fn hit_scene(ray: &Ray, scene: &Scene, step_limit: Interval) -> Option<(HittingInfo, Rc<dyn Material>)> {
+    fn hit_scene(
+        ray: &Ray,
+        scene: &Scene,
+        step_limit: Interval,
+        counters: &mut TraceCounters
+    ) -> Option<(HittingInfo, Rc<dyn Material>)> {
+        counters.segments += 1;
+        counters.entity_tests.resize(scene.entities.len(), 0);
         let mut min_step = INFINITY;
         let mut current_hit = None;
         let mut current_mat = None;
 
         for i in 0..scene.entities.len() {
-            if let Some(hit) = scene.entities[i].mesh.hit(ray, step_limit) {
+            if let Some(hit) = Self::hit_entity(&scene.entities[i], ray, step_limit, &mut counters.entity_tests[i]) {
                 if min_step > hit.step {
                     min_step = hit.step;
                     current_hit = Some(hit);
@@ -47,7 +86,28 @@ impl STDrivenRenderer {
         else { return None; }
     }
 
-    fn ray_color(ray: Ray, depth: u32, scene: &Scene) -> Vec3 {
+    /// Hit an entity, skipping the parts cut out by its opacity mask.
+    fn hit_entity(entity: &Entity, ray: &Ray, mut step_limit: Interval, tests: &mut u64) -> Option<HittingInfo> {
+        loop {
+            *tests += 1;
+            let hit = entity.mesh.hit(ray, step_limit)?;
+
+            let Some(alpha) = &entity.alpha else { return Some(hit); };
+            let alpha = alpha.evaluate_scalar(hit.uv, hit.position);
+            if alpha >= 1.0 { return Some(hit); }
+
+            // a random number hashed from the ray and hit, so the decision is reproducible
+            // and does not take dimensions from sampler.
+            let bits = [ray.ori.x, ray.ori.y, ray.ori.z, ray.dir.x, ray.dir.y, ray.dir.z, hit.step].map(f64::to_bits);
+            let u = (hash(&bits) >> 11) as f64 / (1u64 << 53) as f64;
+            if u < alpha { return Some(hit); }
+
+            // pass through, and look for the next hit behind.
+            step_limit.min = hit.step;
+        }
+    }
+
+    fn ray_color(ray: Ray, path: PathState, scene: &Scene, sampler: &mut dyn Sampler, counters: &mut TraceCounters) -> Vec3 {
         /*
          * There are three situations that `ray_color` will return:
          * 1. if ray hit a light(emissive material), return light color.
@@ -59,36 +119,93 @@ impl STDrivenRenderer {
          *    just return (0.0, 0.0, 0.0) to make pixel's color dark.
         */
 
-        if depth <= 0 { return Vec3::from_scalar(0.0); }
+        if path.depth <= 0 { return Vec3::from_scalar(0.0); }
+
+        let step_limit = Interval::new(0.001, INFINITY);
+
+        let Some((rec, mat)) = Self::hit_scene(&ray, scene, step_limit, counters)
+        else {
+            return Self::background_color(ray, scene);
+        };
+
+        Self::surface_color(ray, rec, mat, path, scene, sampler, counters)
+    }
+
+    /// Trace a primary ray, returning its color and coverage.
+    fn primary_sample(
+        config: &STDrivenRendererConfig,
+        ray: Ray,
+        scene: &Scene,
+        sampler: &mut dyn Sampler,
+        counters: &mut TraceCounters
+    ) -> (Vec3, f64) {
+        if config.max_depth == 0 { return (Vec3::from_scalar(0.0), 1.0); }
 
         let step_limit = Interval::new(0.001, INFINITY);
 
-        let Some((rec, mat)) = Self::hit_scene(&ray, scene, step_limit)
+        let Some((rec, mat)) = Self::hit_scene(&ray, scene, step_limit, counters)
         else {
-            let bg_input = MatInput {
-                incident_ray: ray,
-                // the next three arguments are uesless.
-                surface_norm: Vec3::from_scalar(0.0),
-                surface_front: true,
-                hitted_position: Vec3::from_scalar(0.0)
-            };
-            return scene.background.emissive(bg_input);
+            if config.transparent_bg {
+                return (Vec3::from_scalar(0.0), 0.0);
+            }
+            return (Self::background_color(ray, scene), 1.0);
+        };
+
+        let path = PathState { depth: config.max_depth, wavelength: None };
+        (Self::surface_color(ray, rec, mat, path, scene, sampler, counters), 1.0)
+    }
+
+    fn background_color(ray: Ray, scene: &Scene) -> Vec3 {
+        let bg_input = MatInput {
+            incident_ray: ray,
+            // the next arguments are uesless.
+            surface_norm: Vec3::from_scalar(0.0),
+            geometric_norm: Vec3::from_scalar(0.0),
+            surface_front: true,
+            hitted_position: Vec3::from_scalar(0.0),
+            uv: Vec2::from_scalar(0.0),
+            tangent: Vec3::from_scalar(0.0),
+            bitangent: Vec3::from_scalar(0.0),
+            wavelength: None
         };
+        scene.background.emissive(bg_input)
+    }
 
+    fn surface_color(
+        ray: Ray,
+        rec: HittingInfo,
+        mat: Rc<dyn Material>,
+        path: PathState,
+        scene: &Scene,
+        sampler: &mut dyn Sampler,
+        counters: &mut TraceCounters
+    ) -> Vec3 {
         let mat_input = MatInput {
             incident_ray: ray,
             surface_norm: rec.normal,
+            geometric_norm: rec.geometric_normal,
             surface_front: rec.is_front,
-            hitted_position: rec.position
+            hitted_position: rec.position,
+            uv: rec.uv,
+            tangent: rec.tangent,
+            bitangent: rec.bitangent,
+            wavelength: path.wavelength
         };
         
         let emissive_color = mat.emissive(mat_input);
 
-        let Some(shade_output) = mat.shade(mat_input) else {
+        let Some(mut shade_output) = mat.shade(mat_input, sampler) else {
             return emissive_color;
         };
 
-        let scatter_color = shade_output.attenuation * Self::ray_color(shade_output.scatter, depth - 1, scene);
+        // offset the origin along the geometric normal, to the side where the ray leaves,
+        // so the ray won't hit the surface it starts from.
+        let side = if shade_output.scatter.dir.dot(rec.geometric_normal) >= 0.0 { 1.0 } else { -1.0 };
+        shade_output.scatter.ori += rec.geometric_normal * (side * Self::RAY_OFFSET);
+
+        // once a surface selects a wavelength, the rest of path is traced at it.
+        let next = PathState { depth: path.depth - 1, wavelength: path.wavelength.or(shade_output.wavelength) };
+        let scatter_color = shade_output.attenuation * Self::ray_color(shade_output.scatter, next, scene, sampler, counters);
 
         return emissive_color + scatter_color;
     }
@@ -96,16 +213,43 @@ impl STDrivenRenderer {
 
 impl NativeRenderer for STDrivenRenderer {
     fn submit(&mut self, task: RenderTask) {
-        if task.rays.is_empty() { return; }
+        let sampler = self.sampler.as_mut();
 
-        let sample_scalar = 1.0 / task.rays.len() as f64;
-        
-        for ray in task.rays {
-            self.framebuffer[task.index] += sample_scalar * Self::ray_color(ray, self.config.max_depth, task.scene);
+        for (pixel, samples) in task.tile.pixels().zip(task.samples.iter().cloned()) {
+            if samples.is_empty() { continue; }
+            if task.interrupted() { break; }
+            let pixel_index = self.framebuffer.index_of(pixel);
+
+            for index in samples {
+                sampler.start_sample(pixel, index);
+
+                let sample = task.rays.sample(pixel, sampler);
+                self.counters.segments = 0;
+                let (color, coverage) = Self::primary_sample(&self.config, sample.ray, task.scene, sampler, &mut self.counters);
+                self.stats.record_path(self.counters.segments);
+
+                self.framebuffer.splat(sample.film, color, coverage, self.config.filter.as_ref());
+                self.framebuffer.record(pixel_index, color);
+            }
+        }
+
+        // fold intersection tests of entities by their primitive kinds.
+        for (entity, tests) in task.scene.entities.iter().zip(self.counters.entity_tests.iter_mut()) {
+            if *tests == 0 { continue; }
+            *self.stats.intersection_tests.entry(entity.mesh.kind()).or_insert(0) += *tests;
+            *tests = 0;
         }
     }
 
-    fn fetch(&self) -> Vec<Vec3> {
-        self.framebuffer.clone()
+    fn restore(&mut self, framebuffer: FrameBuffer) {
+        self.framebuffer = framebuffer;
+    }
+
+    fn stats(&self) -> &RenderStats {
+        &self.stats
+    }
+
+    fn framebuffer(&self) -> &FrameBuffer {
+        &self.framebuffer
     }
 }
\ No newline at end of file
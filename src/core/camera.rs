@@ -1,4 +1,4 @@
-use super::{ Vec3, Vec2, Ray };
+use super::{ Vec3, Vec2, Ray, Sampler };
 
 /// Camera abstraction
 #[derive(Clone, Copy)]
@@ -9,9 +9,17 @@ pub struct Camera {
     pub viewport: Vec2, 
 }
 
-/// Pixel Rendering Task Iterator
+/// A camera ray with the film position it samples.
+#[derive(Clone, Copy)]
+pub struct CameraSample {
+    pub ray: Ray,
+    /// Position on film in pixel unit, where pixel `(u, v)` centers at `(u, v)`.
+    pub film: Vec2
+}
+
+/// Camera Ray Generator
 pub struct RayCollection {
-    /// The owner camera of iterator.
+    /// The owner camera of generator.
     owner: Camera,
     /// the left-upper point of viewport (aka the beginning point).
     origin: Vec3,
@@ -25,13 +33,7 @@ pub struct RayCollection {
     /// - UV coordinate origin point is in left-upper corner.
     uvdir: (Vec3, Vec3),
     /// screen size.
-    screen: (u32, u32),
-    /// The number of sample time per pixel.
-    spp: u32,
-
-    // Helper variables for iteration.
-    ending: usize,
-    counter: usize
+    screen: (u32, u32)
 }
 
 impl Camera {
@@ -48,13 +50,13 @@ impl Camera {
     }
 
     /// Get ray's collection.
-    pub fn rays(&self, screen: (u32, u32), spp: u32) -> RayCollection {
-        RayCollection::new(*self, screen, spp)
+    pub fn rays(&self, screen: (u32, u32)) -> RayCollection {
+        RayCollection::new(*self, screen)
     }
 }
 
 impl RayCollection {
-    pub fn new(owner: Camera, screen: (u32, u32), spp: u32) -> RayCollection {
+    pub fn new(owner: Camera, screen: (u32, u32)) -> RayCollection {
         // Caculate the world position of 
         // the first pixel(on left-upper side) of viewport.
         let cam_right = Vec3::new(0.0, 1.0, 0.0)
@@ -70,40 +72,28 @@ impl RayCollection {
         let focal_len = owner.viewport.y / ( 2.0 * f64::tan(owner.fov));
         let origin = owner.pos + owner.dir.normalized() * focal_len + left_v + up_v;
 
-        let ending = (screen.0 * screen.1) as usize;
-
-        RayCollection { owner, origin, uvdir, screen, spp, ending, counter: 0 }
+        RayCollection { owner, origin, uvdir, screen }
     }
-}
 
-impl Iterator for RayCollection {
-    type Item = Vec<Ray>;
-
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.counter == self.ending {
-            return None;
-        }
-        else {
-            let mut rays = Vec::new();
+    /// Get the screen size of generator.
+    pub fn screen(&self) -> (u32, u32) {
+        self.screen
+    }
 
-            for _ in 0..self.spp {
-                let offset = Vec2::random(0.0, 1.0) - 0.5;
+    /// Generate a camera ray jittered within pixel.
+    /// 
+    /// The jitter consumes the next 2D sample of `sampler`.
+    pub fn sample(&self, pixel: (u32, u32), sampler: &mut dyn Sampler) -> CameraSample {
+        let offset = sampler.next_2d() - 0.5;
 
-                let u = self.counter % self.screen.0 as usize;
-                let v = self.counter / self.screen.0 as usize;
-    
-                let u_scaler = (u as f64 + offset.x) / self.screen.0 as f64;
-                let v_scaler = (v as f64 + offset.y) / self.screen.1 as f64;
-                let direction = self.origin + self.uvdir.0 * u_scaler + self.uvdir.1 * v_scaler;
-    
-                let ray = Ray::new(self.owner.pos, direction);
+        let film = Vec2::new(pixel.0 as f64 + offset.x, pixel.1 as f64 + offset.y);
 
-                rays.push(ray);
-            }
+        let u_scaler = film.x / self.screen.0 as f64;
+        let v_scaler = film.y / self.screen.1 as f64;
+        let direction = self.origin + self.uvdir.0 * u_scaler + self.uvdir.1 * v_scaler;
 
-            self.counter += 1;
+        let ray = Ray::new(self.owner.pos, direction);
 
-            return Some(rays);
-        }
+        CameraSample { ray, film }
     }
-}
\ No newline at end of file
+}
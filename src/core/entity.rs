@@ -1,16 +1,26 @@
 use std::rc::Rc;
-use super::{ Material, Hittable };
+use super::{ Material, Hittable, TexParam };
 
 pub struct Entity {
     /// Material of enity.
     pub mat: Rc<dyn Material>,
     /// Mesh of enity.
-    pub mesh: Rc<dyn Hittable>
+    pub mesh: Rc<dyn Hittable>,
+    /// Opacity mask of enity in `[0, 1]`, whose first channel is used.
+    /// 
+    /// Rays pass through transparent parts, and through translucent parts stochastically.
+    pub alpha: Option<TexParam>
 }
 
 impl Entity {
     /// Create a new enity with a material and mesh.
     pub fn new(mat: Rc<dyn Material>, mesh: Rc<dyn Hittable>) -> Entity {
-        Entity { mat, mesh }
+        Entity { mat, mesh, alpha: None }
     }
-}
\ No newline at end of file
+
+    /// Cut out the entity by an opacity mask.
+    pub fn with_alpha(mut self, alpha: impl Into<TexParam>) -> Entity {
+        self.alpha = Some(alpha.into());
+        self
+    }
+}
@@ -0,0 +1,15 @@
+use super::Vec2;
+
+/// Abstraction for pixel reconstruction filter.
+/// 
+/// Each sample contributes to every pixel whose center lies within
+/// the filter's radius, weighted by [`Filter::evaluate`].
+pub trait Filter {
+    /// The radius of filter's support in pixel unit.
+    fn radius(&self) -> f64;
+
+    /// Evaluate filter's weight at the offset from pixel center.
+    /// 
+    /// The offset is in pixel unit, and the weight must be `0.0` outside radius.
+    fn evaluate(&self, offset: Vec2) -> f64;
+}
@@ -0,0 +1,259 @@
+use super::{ Vec2, Vec3, Filter, Tile };
+
+/// Rendering result of a frame.
+/// 
+/// Samples are accumulated as filter-weighted sums,
+/// and the value of pixel is the sum divided by its total weight.
+#[derive(Clone)]
+pub struct FrameBuffer {
+    /// The width and height of buffer in pixel.
+    pub size: (u32, u32),
+    /// The position of buffer's top-left pixel in the frame,
+    /// which is not zero if buffer only covers a window of the frame.
+    pub offset: (u32, u32),
+    /// Weighted sum of sample radiance, which is premultiplied by coverage.
+    pub color_sum: Vec<Vec3>,
+    /// Weighted sum of sample coverage, aka. the primary rays hitting the scene.
+    /// 
+    /// **NOTE:** background counts as covered unless it is transparent.
+    pub alpha_sum: Vec<f64>,
+    /// Sum of filter weights.
+    pub weight_sum: Vec<f64>,
+    /// The number of samples taken in each pixel.
+    pub sample_count: Vec<u32>,
+    /// Sum of the luminance of samples taken in each pixel.
+    pub luminance_sum: Vec<f64>,
+    /// Sum of the squared luminance of samples taken in each pixel.
+    pub luminance_sq_sum: Vec<f64>
+}
+
+impl FrameBuffer {
+    /// Create a black and fully transparent framebuffer.
+    pub fn new(size: (u32, u32)) -> FrameBuffer {
+        Self::with_offset(size, (0, 0))
+    }
+
+    /// Create a black and fully transparent framebuffer covering a window of the frame.
+    pub fn with_offset(size: (u32, u32), offset: (u32, u32)) -> FrameBuffer {
+        let len = (size.0 * size.1) as usize;
+
+        FrameBuffer {
+            size,
+            offset,
+            color_sum: vec![Vec3::from_scalar(0.0); len],
+            alpha_sum: vec![0.0; len],
+            weight_sum: vec![0.0; len],
+            sample_count: vec![0; len],
+            luminance_sum: vec![0.0; len],
+            luminance_sq_sum: vec![0.0; len]
+        }
+    }
+
+    /// Get the number of pixels.
+    pub fn len(&self) -> usize {
+        self.color_sum.len()
+    }
+
+    /// Whether the framebuffer contains no pixel.
+    pub fn is_empty(&self) -> bool {
+        self.color_sum.is_empty()
+    }
+
+    /// Get the window of the frame covered by buffer.
+    pub fn region(&self) -> Tile {
+        Tile {
+            min: self.offset,
+            max: (self.offset.0 + self.size.0, self.offset.1 + self.size.1)
+        }
+    }
+
+    /// Get the index of `pixel`, which is given in the frame.
+    pub fn index_of(&self, pixel: (u32, u32)) -> usize {
+        ((pixel.1 - self.offset.1) * self.size.0 + pixel.0 - self.offset.0) as usize
+    }
+
+    /// Copy pixels inside `window` of the frame into a new buffer.
+    /// 
+    /// # Panics
+    /// Panics if `window` is not inside the buffer.
+    pub fn crop(&self, window: Tile) -> FrameBuffer {
+        let region = self.region();
+        if window.min.0 < region.min.0 || window.min.1 < region.min.1
+            || window.max.0 > region.max.0 || window.max.1 > region.max.1 {
+            panic!("window {:?} is out of framebuffer {:?}!", window, region);
+        }
+
+        let mut cropped = FrameBuffer::with_offset(window.size(), window.min);
+        for pixel in window.pixels() {
+            cropped.copy_pixel(cropped.index_of(pixel), self, self.index_of(pixel));
+        }
+        cropped
+    }
+
+    /// Place buffer into a full frame of `size`, where pixels outside buffer are black and transparent.
+    pub fn uncrop(&self, size: (u32, u32)) -> FrameBuffer {
+        let mut full = FrameBuffer::new(size);
+        for (x, y) in self.region().pixels().filter(|&(x, y)| x < size.0 && y < size.1) {
+            full.copy_pixel(full.index_of((x, y)), self, self.index_of((x, y)));
+        }
+        full
+    }
+
+    /// Pixels whose total weight is below it are treated as having no samples.
+    ///
+    /// Negative lobes of filters (e.g. mitchell and lanczos) can drive the sum to zero or below,
+    /// and dividing by it would blow up or flip the pixel.
+    pub const MIN_WEIGHT: f64 = 1e-3;
+
+    /// Get the color of pixel, which is premultiplied by alpha.
+    pub fn color(&self, index: usize) -> Vec3 {
+        let weight = self.weight_sum[index];
+        if weight > Self::MIN_WEIGHT { self.color_sum[index] / weight }
+        else { Vec3::from_scalar(0.0) }
+    }
+
+    /// Get the alpha (coverage) of pixel.
+    pub fn alpha(&self, index: usize) -> f64 {
+        let weight = self.weight_sum[index];
+        if weight > Self::MIN_WEIGHT { self.alpha_sum[index] / weight }
+        else { 0.0 }
+    }
+
+    /// Get the straight (un-premultiplied) color of pixel.
+    pub fn straight_color(&self, index: usize) -> Vec3 {
+        let alpha = self.alpha(index);
+        if alpha > 0.0 { self.color(index) / alpha }
+        else { Vec3::from_scalar(0.0) }
+    }
+
+    /// Get the relative standard error of pixel's luminance estimation.
+    /// 
+    /// Pixels with less than two samples have infinite error.
+    pub fn relative_error(&self, index: usize) -> f64 {
+        let count = self.sample_count[index] as f64;
+        if count < 2.0 { return f64::INFINITY; }
+
+        let mean = self.luminance_sum[index] / count;
+        let variance = ((self.luminance_sq_sum[index] / count - mean * mean) * count / (count - 1.0)).max(0.0);
+
+        (variance / count).sqrt() / mean.max(1e-3)
+    }
+
+    /// Record the statistics of a sample taken in pixel.
+    pub fn record(&mut self, index: usize, color: Vec3) {
+        let luminance = 0.2126 * color.x + 0.7152 * color.y + 0.0722 * color.z;
+
+        self.sample_count[index] += 1;
+        self.luminance_sum[index] += luminance;
+        self.luminance_sq_sum[index] += luminance * luminance;
+    }
+
+    /// Splat a sample onto every pixel within the filter's radius.
+    /// 
+    /// - `film` the sample's position in pixel unit, where pixel `(u, v)` of the frame centers at `(u, v)`.
+    pub fn splat(&mut self, film: Vec2, color: Vec3, alpha: f64, filter: &dyn Filter) {
+        let radius = filter.radius();
+        let film = film - Vec2::new(self.offset.0 as f64, self.offset.1 as f64);
+
+        let x_min = (film.x - radius).ceil().max(0.0) as u32;
+        let y_min = (film.y - radius).ceil().max(0.0) as u32;
+        let x_max = (film.x + radius).floor().min(self.size.0 as f64 - 1.0);
+        let y_max = (film.y + radius).floor().min(self.size.1 as f64 - 1.0);
+        if x_max < 0.0 || y_max < 0.0 { return; }
+
+        for y in y_min..=y_max as u32 {
+            for x in x_min..=x_max as u32 {
+                let weight = filter.evaluate(Vec2::new(x as f64 - film.x, y as f64 - film.y));
+                if weight == 0.0 { continue; }
+
+                let index = (y * self.size.0 + x) as usize;
+                self.color_sum[index] += weight * color;
+                self.alpha_sum[index] += weight * alpha;
+                self.weight_sum[index] += weight;
+            }
+        }
+    }
+
+    fn copy_pixel(&mut self, index: usize, other: &FrameBuffer, other_index: usize) {
+        self.color_sum[index] = other.color_sum[other_index];
+        self.alpha_sum[index] = other.alpha_sum[other_index];
+        self.weight_sum[index] = other.weight_sum[other_index];
+        self.sample_count[index] = other.sample_count[other_index];
+        self.luminance_sum[index] = other.luminance_sum[other_index];
+        self.luminance_sq_sum[index] = other.luminance_sq_sum[other_index];
+    }
+}
+
+#[cfg(test)]
+mod framebuffer_tests {
+    use super::FrameBuffer;
+    use crate::core::Tile;
+    use crate::{ math::{ Vec2, Vec3 }, prefabs::filters::{ BoxFilter, TentFilter, MitchellFilter } };
+
+    #[test]
+    fn box_splat_averages() {
+        let mut fb = FrameBuffer::new((2, 2));
+        let filter = BoxFilter::new(0.5);
+
+        fb.splat(Vec2::new(1.2, 0.1), Vec3::from_scalar(1.0), 1.0, &filter);
+        fb.splat(Vec2::new(0.8, -0.3), Vec3::from_scalar(3.0), 0.0, &filter);
+
+        assert_eq!(fb.color(1), Vec3::from_scalar(2.0));
+        assert_eq!(fb.alpha(1), 0.5);
+        assert_eq!(fb.straight_color(1), Vec3::from_scalar(4.0));
+        assert_eq!(fb.weight_sum, vec![0.0, 2.0, 0.0, 0.0]);
+    }
+
+    #[test]
+    fn wide_splat_crosses_pixels() {
+        let mut fb = FrameBuffer::new((3, 1));
+        let filter = TentFilter::new(1.5);
+
+        fb.splat(Vec2::new(1.0, 0.0), Vec3::from_scalar(1.0), 1.0, &filter);
+
+        assert!(fb.weight_sum.iter().all(|w| *w > 0.0));
+        assert!(fb.weight_sum[1] > fb.weight_sum[0]);
+        assert_eq!(fb.weight_sum[0], fb.weight_sum[2]);
+        assert_eq!(fb.color(0), Vec3::from_scalar(1.0));
+    }
+
+    #[test]
+    fn negative_lobe_is_empty() {
+        let mut fb = FrameBuffer::new((2, 1));
+        let filter = MitchellFilter::new(2.0);
+
+        // pixel 0 lies only in the negative lobe of a sample outside the frame.
+        fb.splat(Vec2::new(-1.5, 0.0), Vec3::from_scalar(1.0), 1.0, &filter);
+        assert!(fb.weight_sum[0] < 0.0);
+        assert_eq!(fb.color(0), Vec3::from_scalar(0.0));
+        assert_eq!(fb.alpha(0), 0.0);
+        assert_eq!(fb.straight_color(0), Vec3::from_scalar(0.0));
+
+        // a sample at its center outweighs the lobe.
+        fb.splat(Vec2::new(0.0, 0.0), Vec3::from_scalar(1.0), 1.0, &filter);
+        assert!((fb.color(0) - Vec3::from_scalar(1.0)).length() < 1e-12);
+    }
+
+    #[test]
+    fn crop_and_uncrop() {
+        let mut fb = FrameBuffer::new((4, 3));
+        let filter = BoxFilter::new(0.5);
+        for (i, pixel) in fb.region().pixels().enumerate() {
+            fb.splat(Vec2::new(pixel.0 as f64, pixel.1 as f64), Vec3::from_scalar(i as f64), 1.0, &filter);
+        }
+
+        let window = Tile { min: (1, 1), max: (3, 3) };
+        let cropped = fb.crop(window);
+        assert_eq!((cropped.size, cropped.offset), ((2, 2), (1, 1)));
+        assert_eq!(cropped.color(cropped.index_of((2, 1))), Vec3::from_scalar(6.0));
+
+        // splatting into window uses frame coordinates.
+        let mut window_fb = FrameBuffer::with_offset((2, 2), (1, 1));
+        window_fb.splat(Vec2::new(2.0, 2.0), Vec3::from_scalar(1.0), 1.0, &filter);
+        assert_eq!(window_fb.weight_sum, vec![0.0, 0.0, 0.0, 1.0]);
+
+        let full = cropped.uncrop((4, 3));
+        assert_eq!(full.weight_sum, vec![0.0, 0.0, 0.0, 0.0, 0.0, 1.0, 1.0, 0.0, 0.0, 1.0, 1.0, 0.0]);
+        assert_eq!(full.color(9), Vec3::from_scalar(9.0));
+    }
+}
\ No newline at end of file
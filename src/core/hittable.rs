@@ -1,23 +1,33 @@
-use super::{ Ray, math::{ Vec3, Interval } };
+use super::{ Ray, math::{ Vec2, Vec3, Interval } };
 
 /// Hitting information
 pub struct HittingInfo {
     /// The hitted position on hittable.
     pub position: Vec3,
-    /// The normal(normalized) of the hitted surface.
+    /// The shading normal(normalized) of the hitted surface,
+    /// which may be interpolated or perturbed and differ from `geometric_normal`.
     /// 
     /// **NOTE**
     /// 
     /// In order to facilite color caculation, 
     /// normal's direction will be inversed if neccearry 
     /// 
-    /// to make it **always** same as ray's direction.
-    /// (aka. `normal.dot(ray.dir) > 0.0`)
+    /// to make it on the same side as `geometric_normal`.
     pub normal: Vec3,
+    /// The normal(normalized) of the actual surface, which is inversed if neccearry
+    /// to make it **always** against ray's direction.
+    /// (aka. `geometric_normal.dot(ray.dir) <= 0.0`)
+    pub geometric_normal: Vec3,
     /// The step of ray when surface is hitted.
     pub step: f64,
-    /// Whether the ray hit the front face.
-    pub is_front: bool
+    /// Whether the ray hit the front face, which is where the outward `geometric_normal` points.
+    pub is_front: bool,
+    /// The surface coordinate of the hitted position, generally in `[0, 1]^2`.
+    pub uv: Vec2,
+    /// The unit direction where `u` increases, which is perpendicular to `normal`.
+    pub tangent: Vec3,
+    /// The unit direction where `v` increases, which is perpendicular to `normal` and `tangent`.
+    pub bitangent: Vec3
 }
 
 /// Hittable object abstraction
@@ -28,4 +38,9 @@ pub trait Hittable {
     /// 
     /// - `step_limit` the interval for ray's step.
     fn hit(&self, ray: &Ray, step_limit: Interval) -> Option<HittingInfo>;
+
+    /// The kind of primitive, which groups intersection tests in [`RenderStats`](super::RenderStats).
+    fn kind(&self) -> &'static str {
+        "unknown"
+    }
 }
\ No newline at end of file
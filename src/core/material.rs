@@ -1,18 +1,63 @@
-use super::{ Vec3, Ray };
+use super::{ Vec2, Vec3, Ray, Sampler };
  
 /// Material's input data-type.
 #[derive(Clone, Copy)]
 pub struct MatInput {
     /// Incident ray.
     pub incident_ray: Ray,
-    /// The normal vector, whose direction always same as `incident_ray`.
-    /// 
-    /// aka. `dot(incident_ray, surface_norm) > 0`
+    /// The shading normal vector, which is on the same side as `geometric_norm`,
+    /// and is what materials scatter around.
     pub surface_norm: Vec3,
+    /// The normal vector of the actual surface, whose direction is against `incident_ray`.
+    /// 
+    /// aka. `dot(incident_ray, geometric_norm) <= 0`
+    /// 
+    /// Reflected rays must stay on its side, and transmitted rays on the other.
+    pub geometric_norm: Vec3,
     /// Whether the ray hits from the outside surface.
     pub surface_front: bool,
     /// The position where the ray hits on the surface.
-    pub hitted_position: Vec3
+    pub hitted_position: Vec3,
+    /// The surface coordinate where the ray hits, which textures are looked up by.
+    pub uv: Vec2,
+    /// The unit direction where `u` increases on the surface.
+    pub tangent: Vec3,
+    /// The unit direction where `v` increases on the surface.
+    pub bitangent: Vec3,
+    /// The wavelength in nanometers which the path is restricted to,
+    /// or `None` if the path carries all RGB channels.
+    pub wavelength: Option<f64>
+}
+
+impl MatInput {
+    /// Transform a vector from the surface frame `(tangent, bitangent, surface_norm)` into world space.
+    pub fn to_world(&self, local: Vec3) -> Vec3 {
+        self.tangent * local.x + self.bitangent * local.y + self.surface_norm * local.z
+    }
+
+    /// Transform a vector from world space into the surface frame `(tangent, bitangent, surface_norm)`.
+    pub fn to_local(&self, world: Vec3) -> Vec3 {
+        Vec3::new(world.dot(self.tangent), world.dot(self.bitangent), world.dot(self.surface_norm))
+    }
+
+    /// Replace the shading normal, and make tangents perpendicular to it again.
+    /// 
+    /// The geometric normal is unchanged.
+    pub fn with_normal(self, normal: Vec3) -> MatInput {
+        let normal = normal.normalized();
+        let tangent = self.tangent - normal * normal.dot(self.tangent);
+        if tangent.length_square() < 1e-24 {
+            return MatInput { surface_norm: normal, ..self };
+        }
+
+        let tangent = tangent.normalized();
+        let mut bitangent = normal.cross(tangent);
+        if bitangent.dot(self.bitangent) < 0.0 {
+            bitangent = -bitangent;
+        }
+
+        MatInput { surface_norm: normal, tangent, bitangent, ..self }
+    }
 }
 
 /// Material's shading output data-type.
@@ -20,7 +65,12 @@ pub struct ShadeOutput {
     /// The scattered ray after shading.
     pub scatter: Ray,
     /// The attenuation contributed by the surface.
-    pub attenuation: Vec3
+    pub attenuation: Vec3,
+    /// The wavelength in nanometers which the surface restricts the path to,
+    /// like dispersion does, whose RGB weight is included in `attenuation`.
+    /// 
+    /// It must be `None` if the path is restricted already, or the surface doesn't select one.
+    pub wavelength: Option<f64>
 }
 
 /// Abstraction for material.
@@ -33,7 +83,26 @@ pub trait Material {
     }
 
     /// Shading the surface information.
-    fn shade(&self, _input: MatInput) -> Option<ShadeOutput> {
+    /// 
+    /// Random decisions must draw from `sampler`, which continues the current sample.
+    fn shade(&self, _input: MatInput, _sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        None
+    }
+
+    /// Evaluate the scattering function times the cosine term, toward unit direction `wi`
+    /// (pointing away from surface), for integrators which sample lights. (*optional*)
+    /// 
+    /// Return `None` by default, which means the material (e.g. delta or stochastically layered lobes)
+    /// can only be sampled by `shade`, and integrators should fall back to it.
+    fn eval(&self, _input: MatInput, _wi: Vec3) -> Option<Vec3> {
+        None
+    }
+
+    /// Get the density per solid angle that `shade` scatters toward unit direction `wi`. (*optional*)
+    /// 
+    /// Return `None` by default, and must be consistent with `eval` if it is implemented,
+    /// so `attenuation = eval / pdf`.
+    fn pdf(&self, _input: MatInput, _wi: Vec3) -> Option<f64> {
         None
     }
 }
\ No newline at end of file
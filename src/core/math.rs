@@ -1,6 +1,10 @@
 pub mod utils;
+pub mod spectrum;
 
 
+pub mod rng;
+pub use rng::Pcg32;
+
 pub mod interval;
 pub use interval::Interval;
 
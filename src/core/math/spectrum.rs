@@ -0,0 +1,54 @@
+//! Helpers for tracing a path at a single wavelength inside an RGB renderer.
+use core::f64::consts::PI;
+use super::Vec3;
+
+/// The range of visible wavelengths in nanometers, which are sampled uniformly.
+pub const WAVELENGTH_RANGE: (f64, f64) = (380.0, 780.0);
+
+/// Wavelengths in nanometers which represent the red, green and blue channels.
+pub const RGB_WAVELENGTHS: [f64; 3] = [610.0, 545.0, 450.0];
+
+/// Map a uniform number in `[0, 1)` to a wavelength.
+pub fn sample_wavelength(u: f64) -> f64 {
+    WAVELENGTH_RANGE.0 + u * (WAVELENGTH_RANGE.1 - WAVELENGTH_RANGE.0)
+}
+
+/// Get the RGB weight of a wavelength sampled by [`sample_wavelength`].
+/// 
+/// Channels respond to Gaussian bands around [`RGB_WAVELENGTHS`],
+/// which are normalized so that the weight averages to `(1, 1, 1)` over sampled wavelengths.
+///
+/// Narrow bands peak high, up to about `6.4` for blue and `4.0` for red and green, so a single
+/// bright wavelength shows up as fireflies until enough paths are averaged. Hero-wavelength or
+/// stratified wavelength sampling would reduce that variance.
+pub fn wavelength_to_rgb(lambda: f64) -> Vec3 {
+    const WIDTHS: [f64; 3] = [40.0, 40.0, 25.0];
+
+    let range = WAVELENGTH_RANGE.1 - WAVELENGTH_RANGE.0;
+    let band = |c: usize| -> f64 {
+        let (mu, sigma) = (RGB_WAVELENGTHS[c], WIDTHS[c]);
+        let t = (lambda - mu) / sigma;
+        (-0.5 * t * t).exp() * range / (sigma * (2.0 * PI).sqrt())
+    };
+
+    Vec3::new(band(0), band(1), band(2))
+}
+
+#[cfg(test)]
+mod spectrum_tests {
+    use super::*;
+
+    #[test]
+    fn rgb_weights_average_to_white() {
+        let n = 4000;
+        let mut sum = Vec3::from_scalar(0.0);
+        for i in 0..n {
+            sum += wavelength_to_rgb(sample_wavelength((i as f64 + 0.5) / n as f64));
+        }
+        let mean = sum / n as f64;
+        assert!((mean - Vec3::from_scalar(1.0)).length() < 0.01, "{:?}", mean);
+
+        let red = wavelength_to_rgb(RGB_WAVELENGTHS[0]);
+        assert!(red.x > red.y && red.y > red.z);
+    }
+}
@@ -1,7 +1,42 @@
 ///! Collection of useful constants and functions.
 use core::f64;
+use super::{ Vec2, Vec3 };
 
 /// Convert degress to radians.
 pub fn radians(degress: f64) -> f64 {
     f64::consts::PI * degress / 180.0
-}
\ No newline at end of file
+}
+
+/// Map a point in unit square onto unit sphere uniformly.
+pub fn uniform_sphere(u: Vec2) -> Vec3 {
+    let z = 1.0 - 2.0 * u.x;
+    let r = (1.0 - z * z).max(0.0).sqrt();
+    let phi = 2.0 * f64::consts::PI * u.y;
+
+    Vec3::new(r * phi.cos(), r * phi.sin(), z)
+}
+
+/// Mix the bits of integer, which is the finalizer of *SplitMix64*.
+pub fn mix_bits(mut v: u64) -> u64 {
+    v = (v ^ (v >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+    v = (v ^ (v >> 27)).wrapping_mul(0x94d049bb133111eb);
+    v ^ (v >> 31)
+}
+
+/// Hash a set of integers into one.
+pub fn hash(values: &[u64]) -> u64 {
+    values.iter().fold(0x9e3779b97f4a7c15, |h, v| mix_bits(h ^ mix_bits(*v)))
+}
+
+/// Build two unit vectors which form an orthonormal basis with the unit vector `n`.
+pub fn orthonormal_basis(n: Vec3) -> (Vec3, Vec3) {
+    // *Duff et al.*, "Building an Orthonormal Basis, Revisited".
+    let sign = 1.0_f64.copysign(n.z);
+    let a = -1.0 / (sign + n.z);
+    let b = n.x * n.y * a;
+
+    (
+        Vec3::new(1.0 + sign * n.x * n.x * a, sign * b, -sign * n.x),
+        Vec3::new(b, sign + n.y * n.y * a, -n.y)
+    )
+}
@@ -1,6 +1,6 @@
 use std::ops::{ Neg, Add, Sub, Mul, Div };
 use std::cmp::PartialEq;
-use rand::{ self, Rng };
+use rand::Rng;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vec2 {
@@ -18,8 +18,7 @@ impl Vec2 {
     }
 
     /// Generate a random vec2 with each element containing in given interval.
-    pub fn random(min: f64, max: f64) -> Vec2 {
-        let mut rng = rand::thread_rng();
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, min: f64, max: f64) -> Vec2 {
         Vec2 {
             x: rng.gen_range(min..=max),
             y: rng.gen_range(min..=max)
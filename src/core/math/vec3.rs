@@ -4,7 +4,7 @@ use std::{
     cmp::PartialEq
 };
 use super::Interval;
-use rand::{ self, Rng };
+use rand::Rng;
 
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Vec3 {
@@ -22,8 +22,7 @@ impl Vec3 {
     }
 
     /// Generate a random vec3 with each element containing in given interval.
-    pub fn random(min: f64, max: f64) -> Vec3 {
-        let mut rng = rand::thread_rng();
+    pub fn random<R: Rng + ?Sized>(rng: &mut R, min: f64, max: f64) -> Vec3 {
         Vec3 { 
             x: rng.gen_range(min..=max),
             y: rng.gen_range(min..=max),
@@ -32,9 +31,9 @@ impl Vec3 {
     }
 
     /// Generate a random unit vec3.
-    pub fn random_unit() -> Vec3 {
+    pub fn random_unit<R: Rng + ?Sized>(rng: &mut R) -> Vec3 {
         loop {
-            let v = Self::random(-1.0, 1.0);
+            let v = Self::random(rng, -1.0, 1.0);
             if Interval::new(1e-160, 1.0).contains(v.length_square()) {
                 return v.normalized();
             }
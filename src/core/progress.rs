@@ -0,0 +1,61 @@
+use std::time::Duration;
+use super::{ FrameBuffer, renderer::RenderStatus };
+
+/// Progress of rendering, which is reported after every tile.
+#[derive(Debug, Clone, Copy)]
+pub struct Progress {
+    /// The index of current pass, starting from 0.
+    ///
+    /// Pixels take all samples in one pass, unless rendering is adaptive or progressive.
+    pub pass: u32,
+    /// The number of pixels sampled in current pass.
+    pub pixels_done: u64,
+    /// The number of pixels to be sampled in current pass.
+    pub pixels_total: u64,
+    /// The number of samples taken, including the resumed ones.
+    pub samples_done: u64,
+    /// The maximum number of samples to be taken,
+    /// which is an upper bound if rendering is adaptive.
+    pub samples_total: u64,
+    /// The number of rays traced in this rendering.
+    pub rays: u64,
+    /// Time elapsed since rendering started.
+    pub elapsed: Duration,
+    /// Estimated remaining time, which is `None` before any sample is taken.
+    pub eta: Option<Duration>,
+    /// Rays traced per second.
+    pub rays_per_sec: f64
+}
+
+impl Progress {
+    /// Get the fraction of samples taken, in `[0, 1]`.
+    pub fn fraction(&self) -> f64 {
+        if self.samples_total == 0 { return 1.0; }
+        (self.samples_done as f64 / self.samples_total as f64).min(1.0)
+    }
+}
+
+/// Observer of rendering events.
+///
+/// All methods do nothing by default,
+/// and closures of `FnMut(&Progress)` observe the progress only.
+pub trait RenderObserver {
+    /// Called after every tile is rendered.
+    fn on_progress(&mut self, _progress: &Progress) {}
+
+    /// Called with the current estimate whenever a snapshot is due in progressive rendering.
+    fn on_snapshot(&mut self, _framebuffer: &FrameBuffer) {}
+
+    /// Called with the current estimate whenever a checkpoint is due, and once more when rendering stops,
+    /// whether it is finished, cancelled or timed out.
+    fn on_checkpoint(&mut self, _framebuffer: &FrameBuffer) {}
+
+    /// Called once when rendering ends.
+    fn on_finish(&mut self, _progress: &Progress, _status: RenderStatus) {}
+}
+
+impl<F: FnMut(&Progress)> RenderObserver for F {
+    fn on_progress(&mut self, progress: &Progress) {
+        self(progress)
+    }
+}
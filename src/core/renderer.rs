@@ -1,4 +1,9 @@
-use super::{ Camera, Scene, Vec3 };
+use std::{
+    ops::Range, rc::Rc,
+    sync::{ Arc, atomic::{ AtomicBool, Ordering } },
+    time::{ Duration, Instant }
+};
+use super::{ Camera, Scene, FrameBuffer, Filter, Sampler, Tile, TileOrder, Progress, RenderObserver, RenderStats };
 use super::backends::{ 
     NativeRenderer, RenderTask, 
     STDrivenRenderer, STDrivenRendererConfig 
@@ -12,54 +17,325 @@ pub enum BackendConfig {
     CPUDrivenM(u32),
 }
 
+/// Adaptive sampling configurations
+/// 
+/// Every pixel takes `min_spp` samples first, then pixels whose relative error
+/// is above `threshold` keep sampling in batches of `min_spp`
+/// (or `pass_spp` in progressive rendering) until `max_spp`.
+#[derive(Debug, Clone, Copy)]
+pub struct AdaptiveConfig {
+    /// The number of samples every pixel takes.
+    pub min_spp: u32,
+    /// The maximum number of samples per pixel.
+    pub max_spp: u32,
+    /// Relative standard error of pixel's luminance, below which pixel stops sampling.
+    pub threshold: f64
+}
+
+/// Progressive rendering configurations
+/// 
+/// The frame is rendered in passes of `pass_spp` samples over all unfinished pixels,
+/// so the current estimate is usable at any time.
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressiveConfig {
+    /// The number of samples per pixel in each pass.
+    pub pass_spp: u32,
+    /// Interval between snapshots, and taking snapshot after every pass if `None`.
+    pub interval: Option<Duration>
+}
+
+/// Tile scheduling configurations
+#[derive(Debug, Clone, Copy)]
+pub struct TileConfig {
+    /// The width and height of tiles in pixel.
+    pub size: u32,
+    /// The order in which tiles are rendered.
+    pub order: TileOrder
+}
+
+impl Default for TileConfig {
+    fn default() -> Self {
+        TileConfig { size: 16, order: TileOrder::Scanline }
+    }
+}
+
+/// Token to stop rendering cooperatively, which can be cloned and triggered from other threads.
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> CancelToken {
+        CancelToken::default()
+    }
+
+    /// Request rendering to stop, which takes effect before the next pixel.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// How the last rendering ended.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum RenderStatus {
+    /// Every pixel has taken all its samples.
+    Finished,
+    /// Time budget ran out.
+    TimedOut,
+    /// Cancel token was triggered.
+    Cancelled
+}
+
 /// Renderer configurations
 pub struct RendererConfig {
     /// Renderer backend.
     pub backend: BackendConfig,
     /// The maximum number of ray bounce depth.
     pub max_depth: u32,
-    /// The number of sample-times per pixel.
-    pub spp: u32
+    /// The number of sample-times per pixel, which is ignored if `adaptive` is set.
+    pub spp: u32,
+    /// Adaptive sampling configurations, and sampling uniformly with `spp` if `None`.
+    pub adaptive: Option<AdaptiveConfig>,
+    /// Progressive rendering configurations, and rendering all samples of pixel at once if `None`.
+    pub progressive: Option<ProgressiveConfig>,
+    /// Tile scheduling configurations.
+    pub tile: TileConfig,
+    /// The window of the frame to be rendered, and rendering the whole frame if `None`.
+    /// 
+    /// Pixels around the window within filter radius are also traced,
+    /// so pixels inside the window match the ones of a full rendering exactly.
+    pub crop: Option<Tile>,
+    /// Time budget of rendering, and unlimited if `None`.
+    pub time_limit: Option<Duration>,
+    /// Interval between [`on_checkpoint`](RenderObserver::on_checkpoint) during rendering,
+    /// which is still called once when rendering stops if `None`.
+    pub checkpoint_interval: Option<Duration>,
+    /// Token to stop rendering.
+    /// 
+    /// Stopped rendering returns the estimate so far, where the sample counts of pixels are accurate.
+    pub cancel: CancelToken,
+    /// Whether the background is transparent to primary rays.
+    /// 
+    /// Background still lights the scene through secondary rays.
+    pub transparent_bg: bool,
+    /// Pixel reconstruction filter.
+    pub filter: Rc<dyn Filter>,
+    /// Sample generator, which is cloned for each rendering.
+    pub sampler: Box<dyn Sampler>
 }
 
 pub struct Renderer {
     backend: Option<Box<dyn NativeRenderer>>,
-    config: RendererConfig
+    config: RendererConfig,
+    resumed: Option<FrameBuffer>,
+    status: RenderStatus,
+    stats: RenderStats
 }
 
 impl Renderer {
     pub fn new(config: RendererConfig) -> Renderer {
-        Renderer { backend: None, config }
+        Renderer {
+            backend: None, config, resumed: None,
+            status: RenderStatus::Finished,
+            stats: RenderStats::default()
+        }
+    }
+
+    /// Continue the next rendering from an accumulated framebuffer.
+    /// 
+    /// Pixels keep sampling from their sample counts, so resuming with the same
+    /// scene and settings gives the same result as rendering without interruption.
+    /// 
+    /// # Panics
+    /// Rendering panics if framebuffer doesn't cover the [`region`](Renderer::region) to be traced.
+    pub fn resume(&mut self, framebuffer: FrameBuffer) {
+        self.resumed = Some(framebuffer);
+    }
+
+    /// Get how the last rendering ended.
+    pub fn status(&self) -> RenderStatus {
+        self.status
+    }
+
+    /// Get the statistics of the last rendering.
+    pub fn stats(&self) -> &RenderStats {
+        &self.stats
     }
 
-    pub fn render(&mut self, scene: &Scene, camera: &Camera, screen: (u32, u32)) -> Vec<Vec3> {
-        self.init_backend(screen);
+    /// Get the region of the frame to be traced, which is also covered by the rendered framebuffer.
+    /// 
+    /// It is the crop window with a margin of filter radius, or the whole screen if not cropped.
+    /// 
+    /// # Panics
+    /// Panics if crop window is empty or out of screen.
+    pub fn region(&self, screen: (u32, u32)) -> Tile {
+        let Some(window) = self.config.crop else {
+            return Tile { min: (0, 0), max: screen };
+        };
 
-        let mut index = 0;
-        let fb_size = (screen.0 * screen.1) as f64;
+        if window.min.0 >= window.max.0 || window.min.1 >= window.max.1
+            || window.max.0 > screen.0 || window.max.1 > screen.1 {
+            panic!("crop window {:?} is empty or out of screen {:?}!", window, screen);
+        }
+
+        // samples jitter within half a pixel, and splat within filter radius.
+        let margin = (self.config.filter.radius() + 0.5).floor() as u32;
+        Tile {
+            min: (window.min.0.saturating_sub(margin), window.min.1.saturating_sub(margin)),
+            max: ((window.max.0 + margin).min(screen.0), (window.max.1 + margin).min(screen.1))
+        }
+    }
+
+    pub fn render(&mut self, scene: &Scene, camera: &Camera, screen: (u32, u32)) -> FrameBuffer {
+        self.render_with(scene, camera, screen, &mut |_: &Progress| {})
+    }
+
+    /// Render scene, and report progress and snapshots to `observer`.
+    /// 
+    /// The returned framebuffer covers the [`region`](Renderer::region),
+    /// which can be cropped into the exact window by [`FrameBuffer::crop`].
+    pub fn render_with(
+        &mut self,
+        scene: &Scene,
+        camera: &Camera,
+        screen: (u32, u32),
+        observer: &mut dyn RenderObserver
+    ) -> FrameBuffer {
+        let setup_start = Instant::now();
+        let region = self.region(screen);
+        self.init_backend(region);
+
+        let fb_size = region.size().0 * region.size().1;
         let renderer = self.backend.as_mut().unwrap();
+        let rays = camera.rays(screen);
+        let tiles = self.config.tile.order.tiles(region, self.config.tile.size);
+
+        // Pixels below `min_spp` always sample, and ones above it sample only if they are noisy.
+        let (min_spp, max_spp) = match self.config.adaptive {
+            Some(adaptive) => (adaptive.min_spp, adaptive.max_spp),
+            None => (self.config.spp, self.config.spp)
+        };
+        let threshold = self.config.adaptive.map_or(0.0, |x| x.threshold);
+        let pass_spp = match self.config.progressive {
+            Some(progressive) => progressive.pass_spp,
+            None => min_spp
+        }.max(1);
 
-        for rays in camera.rays(screen, self.config.spp) {
-            let progress = index as f64 / fb_size;
-            Self::show_progress(progress);
+        let resumed: u64 = renderer.framebuffer().sample_count.iter()
+                                   .map(|&count| count.min(max_spp) as u64)
+                                   .sum();
+        let mut progress = Progress {
+            pass: 0,
+            pixels_done: 0,
+            pixels_total: 0,
+            samples_done: resumed,
+            samples_total: fb_size as u64 * max_spp as u64,
+            rays: 0,
+            elapsed: Duration::ZERO,
+            eta: None,
+            rays_per_sec: 0.0
+        };
+        let setup_time = setup_start.elapsed();
+        let mut last_snapshot = Instant::now();
+        let mut last_checkpoint = Instant::now();
+        let start = Instant::now();
+        let deadline = self.config.time_limit.map(|limit| start + limit);
+        self.status = RenderStatus::Finished;
+        let mut passes = 0;
 
-            let task = RenderTask { rays, scene, index };
-            renderer.submit(task);
+        'passes: loop {
+            let framebuffer = renderer.framebuffer();
+            let active: Vec<bool> = (0..fb_size as usize).map(|i| {
+                let count = framebuffer.sample_count[i];
+                count < max_spp && (count < min_spp || framebuffer.relative_error(i) > threshold)
+            }).collect();
 
-            index += 1;
+            let active_pixels = active.iter().filter(|&&x| x).count() as u64;
+            if active_pixels == 0 { break; }
+
+            progress.pass = passes;
+            progress.pixels_done = 0;
+            progress.pixels_total = active_pixels;
+            passes += 1;
+
+            for tile in &tiles {
+                let framebuffer = renderer.framebuffer();
+                let samples: Vec<Range<u32>> = tile.pixels().map(|pixel| {
+                    let i = framebuffer.index_of(pixel);
+                    let count = framebuffer.sample_count[i];
+                    if active[i] { count..(count + pass_spp).min(max_spp) } else { count..count }
+                }).collect();
+
+                if samples.iter().all(Range::is_empty) { continue; }
+                let starts: Vec<u32> = samples.iter().map(|x| x.start).collect();
+
+                let task = RenderTask { tile: *tile, samples, rays: &rays, scene, cancel: &self.config.cancel, deadline };
+                renderer.submit(task);
+
+                // the task may stop halfway, so count what has been sampled.
+                let framebuffer = renderer.framebuffer();
+                let taken: Vec<u64> = tile.pixels().zip(starts).map(|(pixel, start)| {
+                    (framebuffer.sample_count[framebuffer.index_of(pixel)] - start) as u64
+                }).collect();
+                progress.pixels_done += taken.iter().filter(|&&x| x > 0).count() as u64;
+                progress.samples_done += taken.iter().sum::<u64>();
+                Self::update_progress(&mut progress, renderer.stats().total_rays(), resumed, start);
+                observer.on_progress(&progress);
+
+                if self.config.checkpoint_interval.is_some_and(|interval| last_checkpoint.elapsed() >= interval) {
+                    observer.on_checkpoint(renderer.framebuffer());
+                    last_checkpoint = Instant::now();
+                }
+
+                if self.config.cancel.is_cancelled() {
+                    self.status = RenderStatus::Cancelled;
+                    break 'passes;
+                }
+                if deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+                    self.status = RenderStatus::TimedOut;
+                    break 'passes;
+                }
+
+                if let Some(ProgressiveConfig { interval: Some(interval), .. }) = self.config.progressive {
+                    if last_snapshot.elapsed() >= interval {
+                        observer.on_snapshot(renderer.framebuffer());
+                        last_snapshot = Instant::now();
+                    }
+                }
+            }
+
+            if let Some(ProgressiveConfig { interval: None, .. }) = self.config.progressive {
+                observer.on_snapshot(renderer.framebuffer());
+            }
         }
 
-        return renderer.fetch();
+        observer.on_checkpoint(renderer.framebuffer());
+
+        Self::update_progress(&mut progress, renderer.stats().total_rays(), resumed, start);
+        self.stats = RenderStats {
+            setup_time,
+            render_time: start.elapsed(),
+            ..renderer.stats().clone()
+        };
+        observer.on_finish(&progress, self.status);
+
+        renderer.fetch()
     }
 
-    fn init_backend(&mut self, screen: (u32, u32)) {
+    fn init_backend(&mut self, region: Tile) {
         match self.config.backend {
             BackendConfig::CPUDrivenS => {
                 let renderer = STDrivenRenderer::new(
                     STDrivenRendererConfig {
-                        fb_size: (screen.0 * screen.1) as usize,
-                        max_depth: self.config.max_depth
-                    }
+                        region,
+                        max_depth: self.config.max_depth,
+                        transparent_bg: self.config.transparent_bg,
+                        filter: Rc::clone(&self.config.filter)
+                    },
+                    self.config.sampler.clone_box()
                 );
 
                 self.backend = Some(Box::new(renderer));
@@ -67,15 +343,420 @@ impl Renderer {
 
             BackendConfig::CPUDrivenM(_) => { panic!("muti-thread renderer hasn't been impl yet!") }
         }
+
+        if let Some(framebuffer) = self.resumed.take() {
+            if framebuffer.region() != region {
+                panic!("resumed framebuffer covers {:?}, which mismatches the region {:?}!", framebuffer.region(), region);
+            }
+            self.backend.as_mut().unwrap().restore(framebuffer);
+        }
     }
 
-    fn show_progress(percentage: f64) {
-        let num_area = format!("{:.0}%", 100.0 * percentage);
-        let prog_area = format!(
-                "[{:<50}]", 
-                String::from("=").repeat((50.0 * percentage) as usize) + ">"
-            );
+    fn update_progress(progress: &mut Progress, rays: u64, resumed: u64, start: Instant) {
+        progress.rays = rays;
+        progress.elapsed = start.elapsed();
+
+        let seconds = progress.elapsed.as_secs_f64();
+        let taken = progress.samples_done - resumed;
+        let remaining = progress.samples_total.saturating_sub(progress.samples_done);
 
-        print!("{:>4} {}\r", num_area, prog_area);
+        progress.eta = if taken > 0 {
+            Some(Duration::from_secs_f64(seconds * remaining as f64 / taken as f64))
+        } else { None };
+        progress.rays_per_sec = if seconds > 0.0 { rays as f64 / seconds } else { 0.0 };
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod renderer_tests {
+    use std::{ cell::RefCell, rc::Rc };
+    use std::time::Duration;
+    use super::{
+        Renderer, RendererConfig, BackendConfig, AdaptiveConfig, ProgressiveConfig, TileConfig,
+        CancelToken, RenderStatus, Progress, RenderObserver
+    };
+    use crate::{ FrameBuffer, core::Tile, utils::checkpoint::{ self, Checkpoint } };
+    use crate::{
+        Camera, Scene, Entity,
+        core::{ Material, MatInput, ShadeOutput, Sampler, Ray },
+        math::{ Vec2, Vec3 },
+        prefabs::{ filters::{ BoxFilter, TentFilter }, materials::{ BgSky, Emissive, Lambertian }, samplers::IndependentSampler, shapes::{ Sphere, Quad } }
+    };
+
+    #[derive(Default)]
+    struct Recorder {
+        snapshots: Vec<Vec<u32>>,
+        progress: Vec<Progress>,
+        finished: Option<RenderStatus>,
+        /// Token triggered at the first snapshot.
+        cancel: Option<CancelToken>,
+        /// File where checkpoints are saved.
+        checkpoint: Option<String>,
+        checkpoints: u32
+    }
+
+    impl RenderObserver for Recorder {
+        fn on_progress(&mut self, progress: &Progress) {
+            self.progress.push(*progress);
+        }
+
+        fn on_snapshot(&mut self, framebuffer: &FrameBuffer) {
+            self.snapshots.push(framebuffer.sample_count.clone());
+            if let Some(cancel) = &self.cancel { cancel.cancel(); }
+        }
+
+        fn on_checkpoint(&mut self, framebuffer: &FrameBuffer) {
+            self.checkpoints += 1;
+            if let Some(name) = &self.checkpoint {
+                checkpoint::save(name, &Checkpoint { scene_hash: 0, framebuffer: framebuffer.clone() }).unwrap();
+            }
+        }
+
+        fn on_finish(&mut self, _progress: &Progress, status: RenderStatus) {
+            self.finished = Some(status);
+        }
+    }
+
+    fn scene() -> Scene {
+        let mut scene = Scene::new(Rc::new(BgSky));
+        scene.add(Entity::new(
+            Rc::new(Lambertian::new(Vec3::from_scalar(0.5))),
+            Rc::new(Sphere::new(Vec3::new(0.0, 0.0, 1.0), 0.5))
+        ));
+        scene
+    }
+
+    fn camera() -> Camera {
+        Camera::new(Vec3::from_scalar(0.0), Vec3::new(0.0, 0.0, 1.0), Vec2::from_scalar(0.5), 45.0)
+    }
+
+    fn config(seed: u64) -> RendererConfig {
+        RendererConfig {
+            backend: BackendConfig::CPUDrivenS,
+            max_depth: 4,
+            spp: 4,
+            adaptive: None,
+            progressive: None,
+            tile: TileConfig::default(),
+            crop: None,
+            time_limit: None,
+            checkpoint_interval: None,
+            cancel: CancelToken::new(),
+            transparent_bg: false,
+            filter: Rc::new(BoxFilter::new(0.5)),
+            sampler: Box::new(IndependentSampler::new(seed))
+        }
+    }
+
+    #[test]
+    fn deterministic() {
+        let (scene, camera) = (scene(), camera());
+
+        let first = Renderer::new(config(42)).render(&scene, &camera, (8, 8));
+        let second = Renderer::new(config(42)).render(&scene, &camera, (8, 8));
+        let other = Renderer::new(config(7)).render(&scene, &camera, (8, 8));
+
+        assert_eq!(first.color_sum, second.color_sum);
+        assert_ne!(first.color_sum, other.color_sum);
+    }
+
+    #[test]
+    fn adaptive() {
+        let (scene, camera) = (scene(), camera());
+        let mut config = config(42);
+        config.adaptive = Some(AdaptiveConfig { min_spp: 4, max_spp: 32, threshold: 0.02 });
+
+        let buffer = Renderer::new(config).render(&scene, &camera, (16, 16));
+        let counts = &buffer.sample_count;
+
+        assert!(counts.iter().all(|&count| (4..=32).contains(&count)));
+        assert!(counts.iter().any(|&count| count == 32));
+        // Sky in the corner has no variance.
+        assert_eq!(counts[0], 4);
+    }
+
+    #[test]
+    fn progressive() {
+        let (scene, camera) = (scene(), camera());
+        let mut per_pass = config(42);
+        per_pass.progressive = Some(ProgressiveConfig { pass_spp: 1, interval: None });
+        let mut timed = config(42);
+        timed.progressive = Some(ProgressiveConfig { pass_spp: 1, interval: Some(Duration::from_secs(3600)) });
+
+        let mut recorder = Recorder::default();
+        let buffer = Renderer::new(per_pass).render_with(&scene, &camera, (8, 8), &mut recorder);
+        let snapshots = recorder.snapshots;
+        let whole = Renderer::new(config(42)).render(&scene, &camera, (8, 8));
+
+        assert_eq!(snapshots.len(), 4);
+        for (pass, counts) in snapshots.iter().enumerate() {
+            assert!(counts.iter().all(|&count| count == pass as u32 + 1));
+        }
+        // Passes continue the sample sequence, which gives the same estimate as rendering at once.
+        assert_eq!(buffer.sample_count, whole.sample_count);
+        assert!(buffer.color_sum.iter().zip(&whole.color_sum).all(|(a, b)| (*a - *b).length() < 1e-9));
+
+        let mut recorder = Recorder::default();
+        Renderer::new(timed).render_with(&scene, &camera, (8, 8), &mut recorder);
+        assert!(recorder.snapshots.is_empty());
+    }
+
+    #[test]
+    fn resume() {
+        let (scene, camera) = (scene(), camera());
+        let mut half = config(42);
+        half.spp = 2;
+
+        let partial = Renderer::new(half).render(&scene, &camera, (8, 8));
+        let mut renderer = Renderer::new(config(42));
+        renderer.resume(partial);
+        let resumed = renderer.render(&scene, &camera, (8, 8));
+        let whole = Renderer::new(config(42)).render(&scene, &camera, (8, 8));
+
+        assert_eq!(resumed.sample_count, whole.sample_count);
+        assert!(resumed.color_sum.iter().zip(&whole.color_sum).all(|(a, b)| (*a - *b).length() < 1e-9));
+    }
+
+    #[test]
+    fn crop() {
+        let (scene, camera) = (scene(), camera());
+        let window = Tile { min: (3, 2), max: (7, 5) };
+        let mut cropped = config(42);
+        cropped.filter = Rc::new(TentFilter::new(1.5));
+        cropped.crop = Some(window);
+        let mut full = config(42);
+        full.filter = Rc::new(TentFilter::new(1.5));
+
+        let mut renderer = Renderer::new(cropped);
+        assert_eq!(renderer.region((8, 8)), Tile { min: (1, 0), max: (8, 7) });
+
+        let part = renderer.render(&scene, &camera, (8, 8)).crop(window);
+        let whole = Renderer::new(full).render(&scene, &camera, (8, 8)).crop(window);
+
+        assert_eq!(part.sample_count, whole.sample_count);
+        assert!(part.color_sum.iter().zip(&whole.color_sum).all(|(a, b)| (*a - *b).length() < 1e-9));
+        assert!(part.weight_sum.iter().zip(&whole.weight_sum).all(|(a, b)| (a - b).abs() < 1e-9));
+    }
+
+    #[test]
+    fn stop_early() {
+        let (scene, camera) = (scene(), camera());
+        let mut timed = config(42);
+        timed.time_limit = Some(Duration::ZERO);
+        let cancel = CancelToken::new();
+        let mut cancelled = config(42);
+        cancelled.cancel = cancel.clone();
+        cancelled.progressive = Some(ProgressiveConfig { pass_spp: 1, interval: None });
+
+        let mut renderer = Renderer::new(timed);
+        let buffer = renderer.render(&scene, &camera, (8, 8));
+        assert_eq!(renderer.status(), RenderStatus::TimedOut);
+        assert!(buffer.sample_count.iter().all(|&count| count == 0));
+
+        // cancel after the first pass.
+        let mut renderer = Renderer::new(cancelled);
+        let mut recorder = Recorder { cancel: Some(cancel), ..Default::default() };
+        let buffer = renderer.render_with(&scene, &camera, (8, 8), &mut recorder);
+        assert_eq!(renderer.status(), RenderStatus::Cancelled);
+        assert!(buffer.sample_count.iter().all(|&count| count == 1));
+
+        let mut renderer = Renderer::new(config(42));
+        renderer.render(&scene, &camera, (8, 8));
+        assert_eq!(renderer.status(), RenderStatus::Finished);
+    }
+
+    /// Absorb rays, and trigger a token once hitted.
+    struct Tripwire(CancelToken);
+
+    impl Material for Tripwire {
+        fn shade(&self, _input: MatInput, _sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+            self.0.cancel();
+            None
+        }
+    }
+
+    #[test]
+    fn stop_within_tile() {
+        let cancel = CancelToken::new();
+        let mut scene = Scene::new(Rc::new(BgSky));
+        scene.add(Entity::new(
+            Rc::new(Tripwire(cancel.clone())),
+            Rc::new(Sphere::new(Vec3::new(0.0, 0.0, 1.0), 0.5))
+        ));
+
+        // the whole frame is a single tile, which stops after the first pixel hitting sphere.
+        let config = RendererConfig { cancel, ..config(42) };
+        let mut renderer = Renderer::new(config);
+        let mut recorder = Recorder::default();
+        let buffer = renderer.render_with(&scene, &camera(), (8, 8), &mut recorder);
+
+        assert_eq!(renderer.status(), RenderStatus::Cancelled);
+        assert!(buffer.sample_count.iter().all(|&count| count == 0 || count == 4));
+        let sampled = buffer.sample_count.iter().filter(|&&count| count == 4).count();
+        assert!(sampled > 0 && sampled < 64, "{}", sampled);
+
+        let last = recorder.progress.last().unwrap();
+        assert_eq!(last.pixels_done, sampled as u64);
+        assert_eq!(last.samples_done, buffer.sample_count.iter().map(|&x| x as u64).sum::<u64>());
+    }
+
+    #[test]
+    fn progress() {
+        let (scene, camera) = (scene(), camera());
+        let mut tiled = config(42);
+        tiled.tile = TileConfig { size: 4, ..Default::default() };
+        tiled.progressive = Some(ProgressiveConfig { pass_spp: 2, interval: None });
+
+        let mut recorder = Recorder::default();
+        Renderer::new(tiled).render_with(&scene, &camera, (8, 8), &mut recorder);
+
+        // 4 tiles per pass, and 2 passes.
+        assert_eq!(recorder.progress.len(), 8);
+        assert_eq!(recorder.finished, Some(RenderStatus::Finished));
+        for (i, progress) in recorder.progress.iter().enumerate() {
+            assert_eq!(progress.pass, i as u32 / 4);
+            assert_eq!(progress.pixels_done, 16 * (i as u64 % 4 + 1));
+            assert_eq!(progress.pixels_total, 64);
+            assert_eq!(progress.samples_done, 32 * (i as u64 + 1));
+            assert!(progress.rays >= progress.samples_done);
+        }
+        assert_eq!(recorder.progress.last().unwrap().fraction(), 1.0);
+    }
+
+    #[test]
+    fn stats() {
+        let (scene, camera) = (scene(), camera());
+        let mut renderer = Renderer::new(config(42));
+        renderer.render(&scene, &camera, (8, 8));
+        let stats = renderer.stats();
+
+        assert_eq!(stats.primary_rays, 8 * 8 * 4);
+        assert_eq!(stats.path_lengths.iter().sum::<u64>(), 8 * 8 * 4);
+        assert_eq!(stats.path_lengths[0], 0);
+        // sky-only paths have one segment, and paths hitting the sphere bounce at least once.
+        assert!(stats.path_lengths[1] > 0 && stats.path_lengths[2..].iter().sum::<u64>() > 0);
+        let segments: u64 = stats.path_lengths.iter().enumerate().map(|(n, &count)| n as u64 * count).sum();
+        assert_eq!(stats.total_rays(), segments);
+        assert_eq!(stats.intersection_tests.get("sphere"), Some(&segments));
+    }
+
+    #[test]
+    fn alpha_cutout() {
+        let camera = camera();
+        let card = |alpha: f64| {
+            let mut scene = scene();
+            scene.add(Entity::new(
+                Rc::new(Lambertian::new(Vec3::new(0.8, 0.1, 0.1))),
+                Rc::new(Quad::new(Vec3::new(-1.0, -1.0, 0.5), Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 2.0, 0.0)))
+            ).with_alpha(alpha));
+            Renderer::new(config(42)).render(&scene, &camera, (8, 8))
+        };
+
+        // rays pass through transparent cards, but not opaque ones.
+        let plain = Renderer::new(config(42)).render(&scene(), &camera, (8, 8));
+        assert_eq!(card(0.0).color_sum, plain.color_sum);
+        assert_ne!(card(1.0).color_sum, plain.color_sum);
+
+        let red = |buffer: &FrameBuffer| buffer.color_sum.iter().map(|c| c.x - c.y).sum::<f64>();
+        let (clear, half, opaque) = (red(&plain), red(&card(0.5)), red(&card(1.0)));
+        assert!(clear < half && half < opaque);
+    }
+
+    #[test]
+    fn checkpoint_when_stopped() {
+        let cancel = CancelToken::new();
+        let mut scene = Scene::new(Rc::new(BgSky));
+        scene.add(Entity::new(
+            Rc::new(Tripwire(cancel.clone())),
+            Rc::new(Sphere::new(Vec3::new(0.0, 0.0, 1.0), 0.5))
+        ));
+        let name = std::env::temp_dir().join("raytracing_checkpoint_when_stopped.ckpt");
+        let name = name.to_str().unwrap().to_string();
+
+        // checkpoints are due after every tile, and once more when cancelled without any snapshot.
+        let config = RendererConfig {
+            cancel,
+            checkpoint_interval: Some(Duration::ZERO),
+            tile: TileConfig { size: 2, ..Default::default() },
+            ..config(42)
+        };
+        let mut renderer = Renderer::new(config);
+        let mut recorder = Recorder { checkpoint: Some(name.clone()), ..Default::default() };
+        let buffer = renderer.render_with(&scene, &camera(), (8, 8), &mut recorder);
+        assert_eq!(renderer.status(), RenderStatus::Cancelled);
+        assert!(recorder.snapshots.is_empty());
+        assert!(recorder.checkpoints > 1);
+
+        let loaded = checkpoint::load(&name).unwrap();
+        std::fs::remove_file(&name).unwrap();
+        assert_eq!(loaded.framebuffer.sample_count, buffer.sample_count);
+        assert!(loaded.framebuffer.sample_count.iter().any(|&count| count == 0));
+    }
+
+    #[test]
+    fn transparent_coverage() {
+        let glow = Vec3::new(0.9, 0.4, 0.1);
+        let mut scene = Scene::new(Rc::new(BgSky));
+        scene.add(Entity::new(
+            Rc::new(Emissive::new(glow)),
+            Rc::new(Sphere::new(Vec3::new(0.0, 0.0, 1.0), 0.5))
+        ));
+        let config = RendererConfig { spp: 16, transparent_bg: true, ..config(42) };
+        let buffer = Renderer::new(config).render(&scene, &camera(), (16, 16));
+
+        // the sky is fully transparent.
+        let corner = buffer.index_of((0, 0));
+        assert_eq!(buffer.alpha(corner), 0.0);
+        assert_eq!(buffer.straight_color(corner), Vec3::from_scalar(0.0));
+
+        // pixels on the silhouette are partially covered, but keep the straight color of surface.
+        let partial = |i: &usize| { let alpha = buffer.alpha(*i); alpha > 0.0 && alpha < 1.0 };
+        let edges: Vec<usize> = (0..buffer.len()).filter(partial).collect();
+        assert!(!edges.is_empty());
+        for i in edges {
+            assert!((buffer.straight_color(i) - glow).length() < 1e-9, "{:?}", buffer.straight_color(i));
+        }
+    }
+
+    /// Pass rays straight through, selecting a wavelength and recording the one of path.
+    struct WavelengthProbe {
+        select: f64,
+        seen: RefCell<Vec<Option<f64>>>
+    }
+
+    impl Material for WavelengthProbe {
+        fn shade(&self, input: MatInput, _sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+            self.seen.borrow_mut().push(input.wavelength);
+            Some(ShadeOutput {
+                scatter: Ray::new(input.hitted_position, input.incident_ray.dir),
+                attenuation: Vec3::from_scalar(1.0),
+                wavelength: Some(self.select)
+            })
+        }
+    }
+
+    #[test]
+    fn wavelength_sticks() {
+        let mut scene = Scene::new(Rc::new(BgSky));
+        let probes: Vec<Rc<WavelengthProbe>> = [500.0, 650.0, 400.0].into_iter().map(|select| {
+            Rc::new(WavelengthProbe { select, seen: RefCell::new(Vec::new()) })
+        }).collect();
+        for (i, probe) in probes.iter().enumerate() {
+            let z = 0.5 + i as f64 * 0.25;
+            scene.add(Entity::new(
+                Rc::clone(probe) as Rc<dyn Material>,
+                Rc::new(Quad::new(Vec3::new(-4.0, -4.0, z), Vec3::new(8.0, 0.0, 0.0), Vec3::new(0.0, 8.0, 0.0)))
+            ));
+        }
+        Renderer::new(config(42)).render(&scene, &camera(), (4, 4));
+
+        // the first surface selects the wavelength, and later ones can't change it.
+        let seen = |i: usize| probes[i].seen.borrow().clone();
+        assert_eq!(seen(0).len(), 4 * 4 * 4);
+        assert!(seen(0).iter().all(Option::is_none));
+        for i in [1, 2] {
+            assert_eq!(seen(i).len(), 4 * 4 * 4);
+            assert!(seen(i).iter().all(|&lambda| lambda == Some(500.0)), "{:?}", seen(i));
+        }
+    }
+}
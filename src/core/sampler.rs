@@ -0,0 +1,22 @@
+use super::Vec2;
+
+/// Abstraction for sample generator.
+/// 
+/// Each sample of a pixel is a point in a high dimensional unit cube,
+/// whose dimensions are consumed in order by camera and integrator.
+/// 
+/// **NOTE:** a sampler must produce the same sample for the same
+/// pixel, sample index and dimension, unless it is purely random.
+pub trait Sampler {
+    /// Start generating the `index`-th sample of pixel from its first dimension.
+    fn start_sample(&mut self, pixel: (u32, u32), index: u32);
+
+    /// Get the next dimension of current sample, which is in `[0, 1)`.
+    fn next_1d(&mut self) -> f64;
+
+    /// Get the next two dimensions of current sample, which are in `[0, 1)^2`.
+    fn next_2d(&mut self) -> Vec2;
+
+    /// Clone sampler into a box.
+    fn clone_box(&self) -> Box<dyn Sampler>;
+}
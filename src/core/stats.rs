@@ -0,0 +1,51 @@
+use std::{ collections::BTreeMap, time::Duration };
+
+/// Statistics of rendering, which are accumulated by backends.
+#[derive(Debug, Clone, Default)]
+pub struct RenderStats {
+    /// Rays shot from camera.
+    pub primary_rays: u64,
+    /// Rays scattered by surfaces.
+    pub secondary_rays: u64,
+    /// Ray-primitive intersection tests, keyed by [`Hittable::kind`](super::Hittable::kind).
+    pub intersection_tests: BTreeMap<&'static str, u64>,
+    /// Histogram of path lengths, where `path_lengths[n]` counts the samples
+    /// whose paths have `n` segments (the primary ray included).
+    pub path_lengths: Vec<u64>,
+    /// Wall time to set up backend and schedule tiles.
+    pub setup_time: Duration,
+    /// Wall time to trace rays.
+    pub render_time: Duration
+}
+
+impl RenderStats {
+    /// Get the number of all rays traced.
+    pub fn total_rays(&self) -> u64 {
+        self.primary_rays + self.secondary_rays
+    }
+
+    /// Get the number of all intersection tests.
+    pub fn total_intersection_tests(&self) -> u64 {
+        self.intersection_tests.values().sum()
+    }
+
+    /// Get million rays traced per second of render time.
+    pub fn mrays_per_sec(&self) -> f64 {
+        let seconds = self.render_time.as_secs_f64();
+        if seconds > 0.0 { self.total_rays() as f64 / seconds / 1e6 }
+        else { 0.0 }
+    }
+
+    /// Record a sample whose path has `length` segments.
+    pub fn record_path(&mut self, length: usize) {
+        if self.path_lengths.len() <= length {
+            self.path_lengths.resize(length + 1, 0);
+        }
+        self.path_lengths[length] += 1;
+
+        if length > 0 {
+            self.primary_rays += 1;
+            self.secondary_rays += length as u64 - 1;
+        }
+    }
+}
@@ -0,0 +1,51 @@
+use std::rc::Rc;
+use super::{ Vec2, Vec3 };
+
+/// Abstraction for texture, which is evaluated at a surface point.
+pub trait Texture {
+    /// Get the value of texture.
+    ///
+    /// - `uv` the surface coordinate reported by [`Hittable`](super::Hittable).
+    /// - `position` the hitted position in world space, used by solid textures.
+    fn evaluate(&self, uv: Vec2, position: Vec3) -> Vec3;
+}
+
+/// Material parameter, which is either a constant or a texture.
+#[derive(Clone)]
+pub enum TexParam {
+    Constant(Vec3),
+    Texture(Rc<dyn Texture>)
+}
+
+impl TexParam {
+    /// Get the value of parameter at a surface point.
+    pub fn evaluate(&self, uv: Vec2, position: Vec3) -> Vec3 {
+        match self {
+            TexParam::Constant(value) => *value,
+            TexParam::Texture(texture) => texture.evaluate(uv, position)
+        }
+    }
+
+    /// Get the scalar value of parameter, which is the first channel.
+    pub fn evaluate_scalar(&self, uv: Vec2, position: Vec3) -> f64 {
+        self.evaluate(uv, position).x
+    }
+}
+
+impl From<Vec3> for TexParam {
+    fn from(value: Vec3) -> Self {
+        TexParam::Constant(value)
+    }
+}
+
+impl From<f64> for TexParam {
+    fn from(value: f64) -> Self {
+        TexParam::Constant(Vec3::from_scalar(value))
+    }
+}
+
+impl From<Rc<dyn Texture>> for TexParam {
+    fn from(texture: Rc<dyn Texture>) -> Self {
+        TexParam::Texture(texture)
+    }
+}
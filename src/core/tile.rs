@@ -0,0 +1,173 @@
+/// A rectangle region of pixels, which is the unit of render scheduling.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Tile {
+    /// The top-left pixel of tile (inclusive).
+    pub min: (u32, u32),
+    /// The bottom-right pixel of tile (exclusive).
+    pub max: (u32, u32)
+}
+
+impl Tile {
+    /// Get the width and height of tile.
+    pub fn size(&self) -> (u32, u32) {
+        (self.max.0 - self.min.0, self.max.1 - self.min.1)
+    }
+
+    /// Iterate pixels of tile in row-major order.
+    pub fn pixels(&self) -> impl Iterator<Item = (u32, u32)> {
+        let (min, max) = (self.min, self.max);
+        (min.1..max.1).flat_map(move |y| (min.0..max.0).map(move |x| (x, y)))
+    }
+}
+
+/// The order in which tiles are rendered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TileOrder {
+    /// Row by row from the top-left tile.
+    Scanline,
+    /// Outwards from the center tile, so the subject usually comes first.
+    Spiral,
+    /// Along the Hilbert curve, which keeps consecutive tiles adjacent.
+    Hilbert
+}
+
+impl TileOrder {
+    /// Split region into tiles of `size` (the right and bottom ones may be smaller),
+    /// and sort them in this order.
+    pub fn tiles(&self, region: Tile, size: u32) -> Vec<Tile> {
+        let size = size.max(1);
+        let (width, height) = region.size();
+        let grid = (width.div_ceil(size), height.div_ceil(size));
+
+        let cells: Vec<(u32, u32)> = match self {
+            TileOrder::Scanline => (0..grid.1).flat_map(|y| (0..grid.0).map(move |x| (x, y))).collect(),
+            TileOrder::Spiral => spiral(grid),
+            TileOrder::Hilbert => hilbert(grid)
+        };
+
+        let (x0, y0) = region.min;
+        cells.into_iter().map(|(x, y)| Tile {
+            min: (x0 + x * size, y0 + y * size),
+            max: (x0 + ((x + 1) * size).min(width), y0 + ((y + 1) * size).min(height))
+        }).collect()
+    }
+}
+
+/* INTERNAL FIELD */
+
+/// Walk a square spiral from the center cell, and keep cells inside grid.
+fn spiral(grid: (u32, u32)) -> Vec<(u32, u32)> {
+    let total = (grid.0 * grid.1) as usize;
+    let mut cells = Vec::with_capacity(total);
+    if total == 0 { return cells; }
+
+    let (mut x, mut y) = ((grid.0 as i64 - 1) / 2, (grid.1 as i64 - 1) / 2);
+    let directions = [(1, 0), (0, 1), (-1, 0), (0, -1)];
+    let (mut dir, mut leg) = (0, 1);
+
+    let visit = |x: i64, y: i64, cells: &mut Vec<(u32, u32)>| {
+        if 0 <= x && x < grid.0 as i64 && 0 <= y && y < grid.1 as i64 {
+            cells.push((x as u32, y as u32));
+        }
+    };
+
+    visit(x, y, &mut cells);
+    while cells.len() < total {
+        // legs grow by one after every two turns: 1, 1, 2, 2, 3, 3 ...
+        for _ in 0..2 {
+            let (dx, dy) = directions[dir];
+            for _ in 0..leg {
+                x += dx;
+                y += dy;
+                visit(x, y, &mut cells);
+            }
+            dir = (dir + 1) % 4;
+        }
+        leg += 1;
+    }
+
+    cells
+}
+
+/// Walk the Hilbert curve covering grid, and keep cells inside grid.
+fn hilbert(grid: (u32, u32)) -> Vec<(u32, u32)> {
+    let n = grid.0.max(grid.1).max(1).next_power_of_two();
+
+    (0..n as u64 * n as u64)
+        .map(|d| hilbert_cell(n, d))
+        .filter(|&(x, y)| x < grid.0 && y < grid.1)
+        .collect()
+}
+
+/// Map distance `d` along the Hilbert curve of `n * n` cells into cell coordinate.
+fn hilbert_cell(n: u32, d: u64) -> (u32, u32) {
+    let (mut x, mut y) = (0u32, 0u32);
+    let mut t = d;
+    let mut s = 1;
+
+    while s < n {
+        let rx = (1 & (t / 2)) as u32;
+        let ry = (1 & (t ^ rx as u64)) as u32;
+
+        // rotate the quadrant.
+        if ry == 0 {
+            if rx == 1 {
+                x = s - 1 - x;
+                y = s - 1 - y;
+            }
+            std::mem::swap(&mut x, &mut y);
+        }
+
+        x += s * rx;
+        y += s * ry;
+        t /= 4;
+        s *= 2;
+    }
+
+    (x, y)
+}
+
+#[cfg(test)]
+mod tile_tests {
+    use super::{ Tile, TileOrder };
+
+    fn screen(size: (u32, u32)) -> Tile {
+        Tile { min: (0, 0), max: size }
+    }
+
+    #[test]
+    fn cover_screen_once() {
+        for order in [TileOrder::Scanline, TileOrder::Spiral, TileOrder::Hilbert] {
+            for (region, size) in [
+                (screen((37, 21)), 8), (screen((64, 64)), 16), (screen((5, 30)), 7),
+                (screen((1, 1)), 32), (Tile { min: (13, 7), max: (40, 29) }, 8)
+            ] {
+                let (width, height) = region.size();
+                let mut covered = vec![0; (width * height) as usize];
+                for tile in order.tiles(region, size) {
+                    for (x, y) in tile.pixels() {
+                        covered[((y - region.min.1) * width + x - region.min.0) as usize] += 1;
+                    }
+                }
+                assert!(covered.iter().all(|&x| x == 1), "{:?} {:?} {}", order, region, size);
+            }
+        }
+    }
+
+    #[test]
+    fn spiral_from_center() {
+        let tiles = TileOrder::Spiral.tiles(screen((50, 50)), 10);
+        assert_eq!(tiles[0].min, (20, 20));
+        assert_eq!(tiles.last().unwrap().size(), (10, 10));
+    }
+
+    #[test]
+    fn hilbert_adjacent() {
+        let tiles = TileOrder::Hilbert.tiles(screen((64, 64)), 8);
+        for pair in tiles.windows(2) {
+            let dx = pair[0].min.0.abs_diff(pair[1].min.0);
+            let dy = pair[0].min.1.abs_diff(pair[1].min.1);
+            assert_eq!(dx + dy, 8);
+        }
+    }
+}
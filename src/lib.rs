@@ -5,13 +5,16 @@ pub use core::math;
 
 pub use core::{
     Camera, 
-    Scene, Entity,
-    Renderer, RendererConfig, BackendConfig
+    Scene, Entity, FrameBuffer,
+    Renderer, RendererConfig, BackendConfig, AdaptiveConfig, ProgressiveConfig, TileConfig, TileOrder, Tile,
+    CancelToken, RenderStatus, Progress, RenderObserver, RenderStats
 };
 
 pub use core::{
     Hittable,
-    Material, MatInput, ShadeOutput
+    Material, MatInput, ShadeOutput,
+    Texture, TexParam,
+    Filter, Sampler
 };
 
 ///! Prefabs shpaes and materials.
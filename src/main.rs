@@ -1,29 +1,81 @@
-use std::{ env, process };
+use std::{ env, fs, io::{ self, Write }, process, rc::Rc, time::{ Duration, Instant } };
 use raytracing::{ 
-    utils::{ cfg_loader, img_saver },
-    Renderer, RendererConfig,
-    Camera, 
+    utils::{ cfg_loader::{ self, ConfigRes }, checkpoint::{ self, Checkpoint }, img_saver::{ self, PostConfig } },
+    Renderer, RendererConfig, ProgressiveConfig, CancelToken, RenderStatus, FrameBuffer,
+    Progress, RenderObserver, RenderStats,
+    Camera,
 };
 
+/// Cancel rendering on Ctrl-C, so the estimate so far is still saved.
+fn cancel_on_interrupt() -> CancelToken {
+    let cancel = CancelToken::new();
+    let handler = cancel.clone();
+    let on_interrupt = move || {
+        // a second Ctrl-C terminates immediately.
+        if handler.is_cancelled() { process::exit(130); }
+        handler.cancel();
+    };
+    if let Err(err) = ctrlc::set_handler(on_interrupt) {
+        eprintln!("warning: failed to handle Ctrl-C: {}", err);
+    }
+    cancel
+}
+
 fn main() {
-    let usage = "usage: raytracing <cfg>";
-    let args: Vec<String> = env::args().collect();
+    let usage = "usage: raytracing <cfg> [--resume [checkpoint]] [--crop x0,y0,x1,y1]";
+    let mut args = env::args().skip(1).peekable();
 
-    if args.len() < 2 {
-        eprintln!("error: no .cfg file provided!");
-        eprintln!("{}", usage);
-        process::exit(-1);
+    let mut cfg_path = None;
+    let mut resume = None;
+    let mut crop = None;
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            // checkpoint defaults to `target.checkpoint` of cfg.
+            "--resume" => resume = Some(args.next_if(|x| !x.starts_with("--"))),
+            // overrides `target.crop` of cfg.
+            "--crop" => {
+                let window = args.next().ok_or("missing crop window").and_then(|x| cfg_loader::parse_crop(&x));
+                match window {
+                    Ok(window) => crop = Some(window),
+                    Err(err) => {
+                        eprintln!("error: invalid \"--crop\": {}", err);
+                        eprintln!("{}", usage);
+                        process::exit(-1);
+                    }
+                }
+            },
+            _ if cfg_path.is_none() && !arg.starts_with("--") => cfg_path = Some(arg),
+            _ => {
+                eprintln!("error: unexpected argument \"{}\"!", arg);
+                eprintln!("{}", usage);
+                process::exit(-1);
+            }
+        }
     }
-    else if args.len() > 2 {
-        eprintln!("error: too many arguments!");
+
+    let Some(cfg_path) = cfg_path else {
+        eprintln!("error: no .cfg file provided!");
         eprintln!("{}", usage);
         process::exit(-1);
-    }
+    };
 
-    print!("Loading configuration from \"{}\" ... ", &args[1]);
-    let mut config = cfg_loader::from_file(&args[1]);
+    print!("Loading configuration from \"{}\" ... ", &cfg_path);
+    let parse_start = Instant::now();
+    let mut config = cfg_loader::from_file(&cfg_path);
+    let parse_time = parse_start.elapsed();
     println!("finished.");
 
+    if crop.is_some() {
+        config.target_crop = crop;
+    }
+
+    let Checkpointing { resume: resume_name, save: checkpoint_name, interval: checkpoint_interval } =
+        checkpointing(resume, &config).unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(-1);
+        });
+
     if !config.comments.is_empty() {
         println!("\n{}", &config.comments);
     }
@@ -31,13 +83,49 @@ fn main() {
     println!("Target Information:");
     println!("> name: {}", config.target_name);
     println!("> pixel: {:#?}", config.target_pixel);
-    println!("> resolution: {:?}\n", config.target_resolution);
+    println!("> resolution: {:?}", config.target_resolution);
+    println!("> tone mapping: {:?}", config.target_tonemap);
+    println!("> exposure: {:+} EV", config.target_exposure);
+    println!("> transfer: {:?}", config.target_transfer);
+    println!("> transparent background: {}", config.target_transparent_bg);
+    match config.target_crop {
+        Some(window) => println!(
+            "> crop: {:?} to {:?} ({} output)",
+            window.min, window.max, if config.target_crop_full { "full" } else { "window" }
+        ),
+        None => println!("> crop: none")
+    }
+    println!("> sample count map: {}", config.target_spp_map.as_deref().unwrap_or("none"));
+    println!("> checkpoint: {}\n", checkpoint_name.as_deref().unwrap_or("none"));
 
     println!("Renderer Features:");
     println!("> backend: {:#?}", config.renderer_backend);
     println!("> BVH acceleration: {}", config.renderer_bvh_acc);
     println!("> Max bounce depth: {}", config.renderer_max_depth);
-    println!("> Sample per pixel: {}\n", config.renderer_spp);
+    match config.renderer_adaptive {
+        Some(adaptive) => println!(
+            "> Sample per pixel: adaptive {}..={} (threshold: {})",
+            adaptive.min_spp, adaptive.max_spp, adaptive.threshold
+        ),
+        None => println!("> Sample per pixel: {}", config.renderer_spp)
+    }
+    match config.renderer_progressive {
+        Some(ProgressiveConfig { pass_spp, interval: Some(interval) }) => println!(
+            "> Progressive: {} spp per pass (snapshot every {:?})", pass_spp, interval
+        ),
+        Some(ProgressiveConfig { pass_spp, interval: None }) => println!(
+            "> Progressive: {} spp per pass (snapshot every pass)", pass_spp
+        ),
+        None => println!("> Progressive: disabled")
+    }
+    println!("> Tiles: {}x{} ({:?} order)", config.renderer_tile.size, config.renderer_tile.size, config.renderer_tile.order);
+    match config.renderer_time_limit {
+        Some(limit) => println!("> Time limit: {:?}", limit),
+        None => println!("> Time limit: none")
+    }
+    println!("> Filter: {} (radius: {})", config.renderer_filter_name, config.renderer_filter.radius());
+    println!("> Sampler: {}", config.renderer_sampler_name);
+    println!("> Seed: {}\n", config.renderer_seed);
 
     println!("Scene entities: {}\n", config.scene.as_ref().unwrap().entities.len());
 
@@ -45,10 +133,36 @@ fn main() {
         RendererConfig {
             backend: config.renderer_backend,
             max_depth: config.renderer_max_depth,
-            spp: config.renderer_spp
+            spp: config.renderer_spp,
+            adaptive: config.renderer_adaptive,
+            progressive: config.renderer_progressive,
+            tile: config.renderer_tile,
+            crop: config.target_crop,
+            time_limit: config.renderer_time_limit,
+            checkpoint_interval,
+            cancel: cancel_on_interrupt(),
+            transparent_bg: config.target_transparent_bg,
+            filter: Rc::clone(&config.renderer_filter),
+            sampler: config.renderer_sampler
         }
     );
 
+    if let Some(name) = &resume_name {
+        let resumed = checkpoint::load(name).unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            process::exit(-1);
+        });
+
+        if resumed.scene_hash != config.scene_hash || resumed.framebuffer.region() != renderer.region(config.target_resolution) {
+            eprintln!("error: checkpoint \"{}\" was rendered with a different scene or settings!", name);
+            process::exit(-1);
+        }
+
+        let samples: u64 = resumed.framebuffer.sample_count.iter().map(|&x| x as u64).sum();
+        println!("Resuming from \"{}\" ({} samples taken).\n", name, samples);
+        renderer.resume(resumed.framebuffer);
+    }
+
     let scene = config.scene.take().unwrap();
 
     let camera = Camera::new(
@@ -59,16 +173,242 @@ fn main() {
     );
 
     println!("Rendering scene...");
-    let framebuffer = renderer.render(&scene, &camera, config.target_resolution);
+    let post = PostConfig {
+        exposure: config.target_exposure,
+        tonemap: config.target_tonemap,
+        transfer: config.target_transfer
+    };
+
+    // Snapshots are written aside and renamed, so an interrupted render never leaves a broken image.
+    let snapshot_name = match config.target_name.rsplit_once('/') {
+        Some((dir, file)) => format!("{}/.{}", dir, file),
+        None => format!(".{}", config.target_name)
+    };
+    // Cropped renderings cover the window and a margin, whose output is the window only or the full frame.
+    let output = |framebuffer: &FrameBuffer| -> FrameBuffer {
+        match config.target_crop {
+            Some(window) if config.target_crop_full => framebuffer.crop(window).uncrop(config.target_resolution),
+            Some(window) => framebuffer.crop(window),
+            None => framebuffer.clone()
+        }
+    };
+    let on_snapshot = |snapshot: &FrameBuffer| {
+        let saved = img_saver::save_as(&snapshot_name, &output(snapshot), post, config.target_pixel)
+                        .and_then(|_| fs::rename(&snapshot_name, &config.target_name).map_err(|err| err.to_string()));
+
+        if let Err(err) = saved {
+            eprintln!("\nwarning: failed to write snapshot: {}", err);
+        }
+    };
+    // Checkpoints are due periodically and when rendering stops, whether images are saved or not.
+    let on_checkpoint = |framebuffer: &FrameBuffer| {
+        let Some(name) = &checkpoint_name else { return };
+        let checkpoint = Checkpoint { scene_hash: config.scene_hash, framebuffer: framebuffer.clone() };
+
+        if let Err(err) = checkpoint::save(name, &checkpoint) {
+            eprintln!("\nwarning: failed to write checkpoint: {}", err);
+        }
+    };
+    let mut observer = TerminalObserver::new(on_snapshot, on_checkpoint);
+    let framebuffer = renderer.render_with(&scene, &camera, config.target_resolution, &mut observer);
+
+    match renderer.status() {
+        RenderStatus::TimedOut => println!("Time limit reached, saving the estimate so far."),
+        RenderStatus::Cancelled => println!("Rendering cancelled, saving the estimate so far."),
+        RenderStatus::Finished => {}
+    }
 
     println!("\nCopying buffer...");
-    img_saver::save_as(
-        &config.target_name, 
-        &framebuffer, 
-        config.target_pixel, 
-        config.target_resolution.0, 
-        config.target_resolution.1
-    ).unwrap();
+    let save_start = Instant::now();
+    img_saver::save_as(&config.target_name, &output(&framebuffer), post, config.target_pixel).unwrap();
+
+    if let Some(name) = &config.target_spp_map {
+        img_saver::save_spp_map(name, &output(&framebuffer)).unwrap();
+    }
+    let save_time = save_start.elapsed();
+
+    print_stats(renderer.stats(), parse_time, save_time);
 
     println!("Done.");
-}
\ No newline at end of file
+}
+
+/// Where rendering resumes from and writes checkpoints to, and how often.
+struct Checkpointing {
+    resume: Option<String>,
+    save: Option<String>,
+    interval: Option<Duration>
+}
+
+/// Resolve checkpoints from `--resume` and `target.checkpoint` of cfg.
+/// 
+/// A resumed rendering keeps checkpointing into the file it resumes from, unless cfg names another one.
+fn checkpointing(resume: Option<Option<String>>, config: &ConfigRes) -> Result<Checkpointing, &'static str> {
+    let resume = match resume {
+        Some(name) => match name.or(config.target_checkpoint.clone()) {
+            Some(name) => Some(name),
+            None => return Err("no checkpoint to resume, which is given by \"--resume <checkpoint>\" or \"target.checkpoint\"!")
+        },
+        None => None
+    };
+    let save = config.target_checkpoint.clone().or(resume.clone());
+    let interval = save.as_ref().map(|_| config.target_checkpoint_interval);
+
+    Ok(Checkpointing { resume, save, interval })
+}
+
+/// Draw progress bar on terminal, and write snapshots and checkpoints by `on_snapshot` and `on_checkpoint`.
+struct TerminalObserver<F: FnMut(&FrameBuffer), C: FnMut(&FrameBuffer)> {
+    on_snapshot: F,
+    on_checkpoint: C,
+    last_draw: Option<Instant>
+}
+
+impl<F: FnMut(&FrameBuffer), C: FnMut(&FrameBuffer)> TerminalObserver<F, C> {
+    fn new(on_snapshot: F, on_checkpoint: C) -> Self {
+        TerminalObserver { on_snapshot, on_checkpoint, last_draw: None }
+    }
+
+    fn draw(progress: &Progress) {
+        let fraction = progress.fraction();
+        let bar = format!("[{:<50}]", String::from("=").repeat((50.0 * fraction) as usize) + ">");
+        let eta = match progress.eta {
+            Some(eta) => format_duration(eta),
+            None => "--:--".to_string()
+        };
+
+        print!(
+            "\r{:>4} {} pass {} | {} / ETA {} | {:.2} Mrays/s ",
+            format!("{:.0}%", 100.0 * fraction), bar, progress.pass + 1,
+            format_duration(progress.elapsed), eta, progress.rays_per_sec / 1e6
+        );
+        let _ = io::stdout().flush();
+    }
+}
+
+impl<F: FnMut(&FrameBuffer), C: FnMut(&FrameBuffer)> RenderObserver for TerminalObserver<F, C> {
+    fn on_progress(&mut self, progress: &Progress) {
+        // redrawing on every tile floods slow terminals.
+        if self.last_draw.is_some_and(|last| last.elapsed() < Duration::from_millis(100)) { return; }
+
+        Self::draw(progress);
+        self.last_draw = Some(Instant::now());
+    }
+
+    fn on_snapshot(&mut self, framebuffer: &FrameBuffer) {
+        (self.on_snapshot)(framebuffer);
+    }
+
+    fn on_checkpoint(&mut self, framebuffer: &FrameBuffer) {
+        (self.on_checkpoint)(framebuffer);
+    }
+
+    fn on_finish(&mut self, progress: &Progress, _status: RenderStatus) {
+        Self::draw(progress);
+        println!();
+    }
+}
+
+fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    if seconds >= 3600 {
+        format!("{}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+    }
+    else { format!("{:02}:{:02}", seconds / 60, seconds % 60) }
+}
+
+fn print_stats(stats: &RenderStats, parse_time: Duration, save_time: Duration) {
+    println!("\nRender Statistics:");
+    println!("> rays: {} (primary: {}, secondary: {})",
+        stats.total_rays(), stats.primary_rays, stats.secondary_rays);
+    println!("> speed: {:.2} Mrays/s", stats.mrays_per_sec());
+
+    println!("> intersection tests: {}", stats.total_intersection_tests());
+    for (kind, tests) in &stats.intersection_tests {
+        println!(">   {}: {}", kind, tests);
+    }
+
+    // long paths are rare, which are merged into the last bucket.
+    let samples: u64 = stats.path_lengths.iter().sum();
+    let buckets: Vec<(String, u64)> = (0..=8).map(|length| {
+        match length {
+            8 => ("8+".to_string(), stats.path_lengths.iter().skip(8).sum()),
+            _ => (length.to_string(), stats.path_lengths.get(length).copied().unwrap_or(0))
+        }
+    }).collect();
+
+    println!("> path length:");
+    for (label, count) in buckets.iter().filter(|(_, count)| *count > 0) {
+        let fraction = *count as f64 / samples.max(1) as f64;
+        println!(">   {:>2}: {:>6.2}% {}", label, 100.0 * fraction, "#".repeat((40.0 * fraction).ceil() as usize));
+    }
+
+    println!("> time: parse {:.3}s, build {:.3}s, render {:.3}s, save {:.3}s\n",
+        parse_time.as_secs_f64(), stats.setup_time.as_secs_f64(),
+        stats.render_time.as_secs_f64(), save_time.as_secs_f64());
+}
+
+#[cfg(test)]
+mod main_tests {
+    use super::*;
+    use raytracing::{
+        Scene, Entity, BackendConfig, TileConfig,
+        math::{ Vec2, Vec3 },
+        prefabs::{ filters::BoxFilter, materials::{ BgSky, Lambertian }, samplers::IndependentSampler, shapes::Sphere }
+    };
+
+    /// Count checkpoints written during rendering.
+    #[derive(Default)]
+    struct Counter(u32);
+
+    impl RenderObserver for Counter {
+        fn on_checkpoint(&mut self, _framebuffer: &FrameBuffer) {
+            self.0 += 1;
+        }
+    }
+
+    #[test]
+    fn resumed_checkpointing() {
+        let mut config = ConfigRes { target_checkpoint_interval: Duration::ZERO, ..Default::default() };
+        assert!(checkpointing(Some(None), &config).is_err());
+
+        // without `target.checkpoint`, a resumed rendering checkpoints into the file it resumes from.
+        let resumed = checkpointing(Some(Some("night.ckpt".to_string())), &config).unwrap();
+        assert_eq!(resumed.resume.as_deref(), Some("night.ckpt"));
+        assert_eq!(resumed.save.as_deref(), Some("night.ckpt"));
+        assert_eq!(resumed.interval, Some(Duration::ZERO));
+        assert!(checkpointing(None, &config).unwrap().interval.is_none());
+
+        config.target_checkpoint = Some("cfg.ckpt".to_string());
+        assert_eq!(checkpointing(Some(None), &config).unwrap().save.as_deref(), Some("cfg.ckpt"));
+
+        // so checkpoints are due during the resumed rendering, not only when it stops.
+        let mut scene = Scene::new(Rc::new(BgSky));
+        scene.add(Entity::new(
+            Rc::new(Lambertian::new(Vec3::from_scalar(0.5))),
+            Rc::new(Sphere::new(Vec3::new(0.0, 0.0, 1.0), 0.5))
+        ));
+        let camera = Camera::new(Vec3::from_scalar(0.0), Vec3::new(0.0, 0.0, 1.0), Vec2::from_scalar(0.5), 45.0);
+        let mut renderer = Renderer::new(RendererConfig {
+            backend: BackendConfig::CPUDrivenS,
+            max_depth: 4,
+            spp: 2,
+            adaptive: None,
+            progressive: None,
+            tile: TileConfig { size: 4, ..Default::default() },
+            crop: None,
+            time_limit: None,
+            checkpoint_interval: resumed.interval,
+            cancel: CancelToken::new(),
+            transparent_bg: false,
+            filter: Rc::new(BoxFilter::new(0.5)),
+            sampler: Box::new(IndependentSampler::new(1))
+        });
+        let mut partial = FrameBuffer::new((8, 8));
+        partial.sample_count.iter_mut().take(16).for_each(|count| *count = 2);
+        renderer.resume(partial);
+
+        let mut counter = Counter::default();
+        renderer.render_with(&scene, &camera, (8, 8), &mut counter);
+        assert!(counter.0 > 1, "{}", counter.0);
+    }
+}
@@ -1,2 +1,5 @@
 pub mod materials;
-pub mod shapes;
\ No newline at end of file
+pub mod shapes;
+pub mod filters;
+pub mod samplers;
+pub mod textures;
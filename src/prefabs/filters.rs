@@ -0,0 +1,64 @@
+pub mod box_filter;
+pub use box_filter::BoxFilter;
+
+pub mod tent;
+pub use tent::TentFilter;
+
+pub mod gaussian;
+pub use gaussian::GaussianFilter;
+
+pub mod mitchell;
+pub use mitchell::MitchellFilter;
+
+pub mod lanczos;
+pub use lanczos::LanczosFilter;
+
+#[cfg(test)]
+mod filters_tests {
+    use super::*;
+    use crate::{ core::Filter, math::Vec2 };
+
+    fn filters() -> Vec<Box<dyn Filter>> {
+        vec![
+            Box::new(BoxFilter::new(0.5)),
+            Box::new(TentFilter::new(1.0)),
+            Box::new(GaussianFilter::new(1.5)),
+            Box::new(MitchellFilter::new(2.0)),
+            Box::new(LanczosFilter::new(3.0))
+        ]
+    }
+
+    #[test]
+    fn peak_at_center() {
+        for filter in filters() {
+            let center = filter.evaluate(Vec2::from_scalar(0.0));
+            assert!(center > 0.0);
+
+            for i in 1..10 {
+                let offset = Vec2::new(0.1 * i as f64, 0.0);
+                assert!(filter.evaluate(offset) <= center);
+            }
+        }
+    }
+
+    #[test]
+    fn symmetric() {
+        for filter in filters() {
+            let offset = Vec2::new(0.3, 0.7);
+            let weight = filter.evaluate(offset);
+
+            assert_eq!(filter.evaluate(-offset), weight);
+            assert_eq!(filter.evaluate(Vec2::new(-0.3, 0.7)), weight);
+        }
+    }
+
+    #[test]
+    fn vanish_outside_radius() {
+        for filter in filters() {
+            let r = filter.radius();
+
+            assert_eq!(filter.evaluate(Vec2::new(r + 1e-6, 0.0)), 0.0);
+            assert_eq!(filter.evaluate(Vec2::new(0.0, r + 1e-6)), 0.0);
+        }
+    }
+}
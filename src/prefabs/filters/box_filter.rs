@@ -0,0 +1,25 @@
+use super::super::super::core::{ Filter, Vec2 };
+
+/// Box filter, which weights every sample within radius equally.
+/// 
+/// A box filter with radius `0.5` averages the samples of each pixel.
+pub struct BoxFilter {
+    pub radius: f64
+}
+
+impl BoxFilter {
+    pub fn new(radius: f64) -> BoxFilter {
+        BoxFilter { radius }
+    }
+}
+
+impl Filter for BoxFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, offset: Vec2) -> f64 {
+        if offset.x.abs() <= self.radius && offset.y.abs() <= self.radius { 1.0 }
+        else { 0.0 }
+    }
+}
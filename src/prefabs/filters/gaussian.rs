@@ -0,0 +1,37 @@
+use super::super::super::core::{ Filter, Vec2 };
+
+/// Gaussian filter, truncated at radius.
+/// 
+/// Gaussian is shifted down by its value at radius, so it falls to zero smoothly.
+pub struct GaussianFilter {
+    pub radius: f64,
+    /// Standard deviation in pixel unit.
+    pub sigma: f64
+}
+
+impl GaussianFilter {
+    /// Create a gaussian filter with `sigma = radius / 3`.
+    pub fn new(radius: f64) -> GaussianFilter {
+        GaussianFilter { radius, sigma: radius / 3.0 }
+    }
+
+    fn gaussian(&self, x: f64) -> f64 {
+        (-x * x / (2.0 * self.sigma * self.sigma)).exp()
+    }
+}
+
+impl Filter for GaussianFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, offset: Vec2) -> f64 {
+        let edge = self.gaussian(self.radius);
+        let weight = |x: f64| {
+            if x.abs() > self.radius { 0.0 }
+            else { (self.gaussian(x) - edge).max(0.0) }
+        };
+
+        weight(offset.x) * weight(offset.y)
+    }
+}
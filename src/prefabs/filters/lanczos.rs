@@ -0,0 +1,40 @@
+use std::f64::consts::PI;
+use super::super::super::core::{ Filter, Vec2 };
+
+/// Lanczos windowed sinc filter.
+pub struct LanczosFilter {
+    pub radius: f64,
+    /// The number of sinc's lobes within radius.
+    pub tau: f64
+}
+
+impl LanczosFilter {
+    /// Create a lanczos filter with `tau = radius`.
+    pub fn new(radius: f64) -> LanczosFilter {
+        LanczosFilter { radius, tau: radius }
+    }
+
+    fn sinc(x: f64) -> f64 {
+        if x.abs() < 1e-5 { 1.0 }
+        else { (PI * x).sin() / (PI * x) }
+    }
+}
+
+impl Filter for LanczosFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, offset: Vec2) -> f64 {
+        let weight = |x: f64| {
+            if x.abs() > self.radius { 0.0 }
+            else {
+                // map radius onto `tau` lobes, and window by the central lobe of another sinc.
+                let x = x / self.radius * self.tau;
+                Self::sinc(x) * Self::sinc(x / self.tau)
+            }
+        };
+
+        weight(offset.x) * weight(offset.y)
+    }
+}
@@ -0,0 +1,49 @@
+use super::super::super::core::{ Filter, Vec2 };
+
+/// Mitchell-Netravali cubic filter.
+/// 
+/// It has negative lobes, which sharpen the image while ringing slightly.
+pub struct MitchellFilter {
+    pub radius: f64,
+    pub b: f64,
+    pub c: f64
+}
+
+impl MitchellFilter {
+    /// Create a mitchell filter with the recommended `B = C = 1/3`.
+    pub fn new(radius: f64) -> MitchellFilter {
+        MitchellFilter { radius, b: 1.0 / 3.0, c: 1.0 / 3.0 }
+    }
+
+    /// The 1D cubic defined on `[-2, 2]`.
+    fn mitchell(&self, x: f64) -> f64 {
+        let (b, c) = (self.b, self.c);
+        let x = x.abs();
+
+        if x > 2.0 { 0.0 }
+        else if x > 1.0 {
+            ((-b - 6.0 * c) * x.powi(3) + (6.0 * b + 30.0 * c) * x.powi(2)
+                + (-12.0 * b - 48.0 * c) * x + (8.0 * b + 24.0 * c)) / 6.0
+        }
+        else {
+            ((12.0 - 9.0 * b - 6.0 * c) * x.powi(3) + (-18.0 + 12.0 * b + 6.0 * c) * x.powi(2)
+                + (6.0 - 2.0 * b)) / 6.0
+        }
+    }
+}
+
+impl Filter for MitchellFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, offset: Vec2) -> f64 {
+        // scale radius onto the cubic's support.
+        let weight = |x: f64| {
+            if x.abs() > self.radius { 0.0 }
+            else { self.mitchell(2.0 * x / self.radius) }
+        };
+
+        weight(offset.x) * weight(offset.y)
+    }
+}
@@ -0,0 +1,23 @@
+use super::super::super::core::{ Filter, Vec2 };
+
+/// Tent (triangle) filter, whose weight falls off linearly to radius.
+pub struct TentFilter {
+    pub radius: f64
+}
+
+impl TentFilter {
+    pub fn new(radius: f64) -> TentFilter {
+        TentFilter { radius }
+    }
+}
+
+impl Filter for TentFilter {
+    fn radius(&self) -> f64 {
+        self.radius
+    }
+
+    fn evaluate(&self, offset: Vec2) -> f64 {
+        let tent = |x: f64| (self.radius - x.abs()).max(0.0);
+        tent(offset.x) * tent(offset.y)
+    }
+}
@@ -1,6 +1,9 @@
 pub mod lambertian;
 pub use lambertian::Lambertian;
 
+pub mod oren_nayar;
+pub use oren_nayar::OrenNayar;
+
 pub mod emissive;
 pub use emissive::Emissive;
 
@@ -8,4 +11,344 @@ pub mod bg_pure;
 pub use bg_pure::BgPure;
 
 pub mod bg_sky;
-pub use bg_sky::BgSky;
\ No newline at end of file
+pub use bg_sky::BgSky;
+
+pub mod normal_map;
+pub use normal_map::NormalMap;
+
+pub mod bump_map;
+pub use bump_map::BumpMap;
+
+pub mod microfacet;
+
+pub mod rough_conductor;
+pub use rough_conductor::RoughConductor;
+
+pub mod rough_dielectric;
+pub use rough_dielectric::RoughDielectric;
+
+pub mod dielectric;
+pub use dielectric::{ Dielectric, Ior };
+
+pub mod thin_film;
+pub use thin_film::ThinFilm;
+
+pub mod principled;
+pub use principled::Principled;
+
+pub mod mix;
+pub use mix::Mix;
+
+pub mod coated;
+pub use coated::Coated;
+
+pub mod two_sided;
+pub use two_sided::TwoSided;
+
+#[cfg(test)]
+mod materials_tests {
+    use std::{ f64::consts::PI, rc::Rc };
+    use super::*;
+    use crate::{
+        core::{ Material, MatInput, ShadeOutput, Sampler, Ray, Texture, TexParam },
+        prefabs::samplers::IndependentSampler,
+        math::{ Vec2, Vec3 }
+    };
+
+    /// Scatter along the shading normal, to observe how it is perturbed.
+    struct NormalProbe;
+
+    impl Material for NormalProbe {
+        fn shade(&self, input: MatInput, _sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+            Some(ShadeOutput {
+                scatter: Ray::new(input.hitted_position, input.surface_norm),
+                attenuation: Vec3::from_scalar(1.0),
+                wavelength: None
+            })
+        }
+    }
+
+    /// Height which grows along `u`.
+    struct Ramp;
+
+    impl Texture for Ramp {
+        fn evaluate(&self, uv: Vec2, _position: Vec3) -> Vec3 {
+            Vec3::from_scalar(uv.x)
+        }
+    }
+
+    /// Input hitting the origin of the `z = 0` plane, whose normal is `+z`, along `dir`.
+    fn input(front: bool, dir: Vec3) -> MatInput {
+        MatInput {
+            incident_ray: Ray::new(-dir, dir),
+            surface_norm: Vec3::new(0.0, 0.0, 1.0),
+            geometric_norm: Vec3::new(0.0, 0.0, 1.0),
+            surface_front: front,
+            hitted_position: Vec3::from_scalar(0.0),
+            uv: Vec2::new(0.5, 0.5),
+            tangent: Vec3::new(1.0, 0.0, 0.0),
+            bitangent: Vec3::new(0.0, 1.0, 0.0),
+            wavelength: None
+        }
+    }
+
+    fn shading_normal(material: &dyn Material, front: bool) -> Vec3 {
+        let normal = if front { Vec3::new(0.0, 0.0, 1.0) } else { Vec3::new(0.0, 0.0, -1.0) };
+        let input = MatInput { surface_norm: normal, geometric_norm: normal, ..input(front, -normal) };
+        let mut sampler = IndependentSampler::new(0);
+        material.shade(input, &mut sampler).unwrap().scatter.dir
+    }
+
+    #[test]
+    fn normal_map() {
+        let flat = NormalMap::new(Rc::new(NormalProbe), Vec3::new(0.5, 0.5, 1.0), 1.0);
+        assert_eq!(shading_normal(&flat, true), Vec3::new(0.0, 0.0, 1.0));
+
+        // tilting toward the tangent tilts the outward normal on both faces.
+        let h = 0.5_f64.sqrt() / 2.0;
+        let tilted = NormalMap::new(Rc::new(NormalProbe), Vec3::new(0.5 + h, 0.5, 0.5 + h), 1.0);
+        let n = shading_normal(&tilted, true);
+        assert!((n.x - 0.5_f64.sqrt()).abs() < 1e-9 && n.z > 0.0);
+        let n = shading_normal(&tilted, false);
+        assert!((n.x + 0.5_f64.sqrt()).abs() < 1e-9 && n.z < 0.0);
+    }
+
+    #[test]
+    fn bump_map() {
+        let ramp: Rc<dyn Texture> = Rc::new(Ramp);
+        let bump = BumpMap::new(Rc::new(NormalProbe), ramp, 1.0);
+
+        // a slope of 1 along the tangent leans the normal by 45 degrees.
+        let n = shading_normal(&bump, true);
+        assert!((n - Vec3::new(-1.0, 0.0, 1.0).normalized()).length() < 1e-6);
+        let n = shading_normal(&bump, false);
+        assert!((n - Vec3::new(1.0, 0.0, -1.0).normalized()).length() < 1e-6);
+    }
+
+    #[test]
+    fn fresnel() {
+        use microfacet::{ fresnel_dielectric, fresnel_conductor };
+
+        assert!((fresnel_dielectric(1.0, 1.5) - 0.04).abs() < 1e-12);
+        assert_eq!(fresnel_dielectric(0.1, 1.0 / 1.5), 1.0);
+
+        // conductors without absorption are dielectrics.
+        for cos in [1.0, 0.7, 0.2] {
+            let r = fresnel_conductor(cos, Vec3::from_scalar(1.5), Vec3::from_scalar(0.0));
+            assert!((r.x - fresnel_dielectric(cos, 1.5)).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn ggx_visible_normals() {
+        use microfacet::Ggx;
+        use crate::math::utils::uniform_sphere;
+
+        let ggx = Ggx::from_roughness(0.9, 0.6);
+        let wo = Vec3::new(0.8, -0.3, 0.3).normalized();
+        let mut sampler = IndependentSampler::new(3);
+        sampler.start_sample((0, 0), 0);
+        let n = 200000;
+
+        // estimate over the hemisphere uniformly, where the projected area of
+        // visible microfacets equals that of the macro surface (`wo.z / G1(wo)`),
+        // and the mean of `m.z` over visible normals.
+        let (mut area, mut mean_z) = (0.0, 0.0);
+        for _ in 0..n {
+            let mut m = uniform_sphere(sampler.next_2d());
+            m.z = m.z.abs();
+            let visible = ggx.d(m) * m.dot(wo).max(0.0) * 2.0 * std::f64::consts::PI;
+            area += visible;
+            mean_z += visible * m.z * ggx.g1(wo) / wo.z;
+        }
+        assert!((area / n as f64 - wo.z / ggx.g1(wo)).abs() < 0.01);
+
+        let mut sampled_z = 0.0;
+        for _ in 0..n {
+            let m = ggx.sample_visible(wo, sampler.next_2d());
+            assert!(m.z > 0.0 && m.dot(wo) >= -1e-9);
+            sampled_z += m.z;
+        }
+        let (sampled_z, mean_z) = (sampled_z / n as f64, mean_z / n as f64);
+        assert!((sampled_z - mean_z).abs() < 0.01, "{} != {}", sampled_z, mean_z);
+    }
+
+    #[test]
+    fn rough_microfacets() {
+        use microfacet::{ fresnel_conductor, fresnel_dielectric };
+
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let mut sampler = IndependentSampler::new(6);
+        sampler.start_sample((0, 0), 0);
+
+        // a nearly smooth metal mirrors with its Fresnel reflectance at normal incidence.
+        let (eta, k) = RoughConductor::preset("gold").unwrap();
+        let gold = RoughConductor::new(eta, k, 0.01, 0.01);
+        let expected = fresnel_conductor(1.0, eta, k);
+        for _ in 0..100 {
+            let output = gold.shade(input(true, down), &mut sampler).unwrap();
+            assert!((output.scatter.dir.normalized() - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-2);
+            assert!((output.attenuation - expected).length() < 1e-3, "{:?} != {:?}", output.attenuation, expected);
+        }
+
+        // glass reflects by its Fresnel reflectance, and transmits into the other side.
+        let glass = RoughDielectric::new(1.5, 0.2, 0.2);
+        let n = 20000;
+        let (mut reflected, mut scattered) = (0, 0);
+        for _ in 0..n {
+            let Some(output) = glass.shade(input(true, down), &mut sampler) else { continue };
+            scattered += 1;
+            if output.scatter.dir.z > 0.0 { reflected += 1; }
+        }
+        let ratio = reflected as f64 / scattered as f64;
+        assert!((ratio - fresnel_dielectric(1.0, 1.5)).abs() < 0.01, "{}", ratio);
+
+        // rays transmitted through the shading normal never leak back over a tilted geometric normal.
+        let frosted = RoughDielectric::new(1.5, 0.8, 0.8);
+        let geometric_norm = Vec3::new(1.0, 0.0, 0.5).normalized();
+        for (front, dir) in [(true, Vec3::new(-0.3, 0.2, -1.0)), (false, Vec3::new(-0.2, -0.1, -1.0))] {
+            let input = MatInput { geometric_norm, ..input(front, dir.normalized()) };
+            let mut transmitted = 0;
+            for _ in 0..2000 {
+                let Some(output) = frosted.shade(input, &mut sampler) else { continue };
+                if output.scatter.dir.z < 0.0 {
+                    transmitted += 1;
+                    assert!(output.scatter.dir.dot(geometric_norm) < 0.0, "{:?}", output.scatter.dir);
+                }
+            }
+            assert!(transmitted > 0);
+        }
+    }
+
+    #[test]
+    fn principled_lobes() {
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let mut sampler = IndependentSampler::new(5);
+        sampler.start_sample((0, 0), 0);
+
+        // a smooth metal mirrors with its base color at normal incidence.
+        let mut metal = Principled::new(Vec3::new(0.9, 0.6, 0.3));
+        metal.metallic = TexParam::from(1.0);
+        metal.roughness = TexParam::from(0.0);
+        let output = metal.shade(input(true, down), &mut sampler).unwrap();
+        assert!((output.scatter.dir - Vec3::new(0.0, 0.0, 1.0)).length() < 1e-3);
+        assert!((output.attenuation - Vec3::new(0.9, 0.6, 0.3)).length() < 1e-3);
+
+        // no lobe gains energy, and glass transmits some rays.
+        let mut glass = Principled::new(Vec3::from_scalar(1.0));
+        glass.transmission = TexParam::from(1.0);
+        glass.clearcoat = TexParam::from(1.0);
+        let mut transmitted = 0;
+        for _ in 0..1000 {
+            let Some(output) = glass.shade(input(true, down), &mut sampler) else { continue };
+            let a = output.attenuation;
+            assert!(a.x <= 1.0 + 1e-9 && a.y <= 1.0 + 1e-9 && a.z <= 1.0 + 1e-9);
+            if output.scatter.dir.z < 0.0 { transmitted += 1; }
+        }
+        assert!(transmitted > 800);
+
+        // full sheen brightens the grazing rim toward white, but never beyond.
+        let mut cloth = Principled::new(Vec3::from_scalar(0.8));
+        cloth.sheen = TexParam::from(1.0);
+        cloth.specular = TexParam::from(0.0);
+        let grazing = input(true, Vec3::new(1.0, 0.0, -0.05).normalized());
+        let mut brightest: f64 = 0.0;
+        for _ in 0..1000 {
+            let Some(output) = cloth.shade(grazing, &mut sampler) else { continue };
+            let a = output.attenuation;
+            assert!(a.x <= 1.0 + 1e-9 && a.y <= 1.0 + 1e-9 && a.z <= 1.0 + 1e-9);
+            brightest = brightest.max(a.x);
+        }
+        assert!(brightest > 0.85, "{}", brightest);
+    }
+
+    #[test]
+    fn combinators() {
+        let down = Vec3::new(0.0, 0.0, -1.0);
+        let red: Rc<dyn Material> = Rc::new(Emissive::new(Vec3::new(1.0, 0.0, 0.0)));
+        let blue: Rc<dyn Material> = Rc::new(Emissive::new(Vec3::new(0.0, 0.0, 1.0)));
+
+        let mix = Mix::new(Rc::clone(&red), Rc::clone(&blue), 0.25);
+        assert_eq!(mix.emissive(input(true, down)), Vec3::new(0.75, 0.0, 0.25));
+
+        let two_sided = TwoSided::new(Rc::clone(&red), Rc::clone(&blue));
+        assert_eq!(two_sided.emissive(input(true, down)), Vec3::new(1.0, 0.0, 0.0));
+        assert_eq!(two_sided.emissive(input(false, down)), Vec3::new(0.0, 0.0, 1.0));
+
+        // a smooth coat reflects about 4% of rays at normal incidence, and passes the rest to the base.
+        let coated = Coated::new(Rc::new(Lambertian::new(0.5)), 1.5, 0.0);
+        let mut sampler = IndependentSampler::new(9);
+        sampler.start_sample((0, 0), 0);
+        let mirrored = (0..10000).filter(|_| {
+            let output = coated.shade(input(true, down), &mut sampler).unwrap();
+            output.attenuation.x > 0.9
+        }).count();
+        assert!((300..500).contains(&mirrored), "{}", mirrored);
+
+        // mixtures can be evaluated only if both materials can.
+        let up = Vec3::new(0.0, 0.0, 1.0);
+        let diffuse = Mix::new(Rc::new(Lambertian::new(0.2)), Rc::new(OrenNayar::new(0.8, 0.0)), 0.5);
+        assert!((diffuse.eval(input(true, down), up).unwrap() - Vec3::from_scalar(0.5 / PI)).length() < 1e-12);
+        let glossy = Mix::new(Rc::new(Lambertian::new(0.2)), Rc::new(coated), 0.5);
+        assert!(glossy.eval(input(true, down), up).is_none());
+        assert!(glossy.pdf(input(true, down), up).is_none());
+    }
+
+    #[test]
+    fn oren_nayar() {
+        let input = input(true, Vec3::new(-1.0, 0.0, -1.0));
+        let lambertian = Lambertian::new(0.5);
+        let smooth = OrenNayar::new(0.5, 0.0);
+        let rough = OrenNayar::new(0.5, 0.5);
+
+        // it is *Lambertian* without roughness, and rough surfaces scatter back toward the viewer.
+        let back = Vec3::new(1.0, 0.0, 0.5).normalized();
+        let forward = Vec3::new(-1.0, 0.0, 0.5).normalized();
+        let eval = |material: &dyn Material, wi| material.eval(input, wi).unwrap();
+        assert!((eval(&smooth, back) - eval(&lambertian, back)).length() < 1e-12);
+        assert!(eval(&rough, back).x > eval(&rough, forward).x);
+
+        // the sampling weight agrees with `eval / pdf`.
+        let mut sampler = IndependentSampler::new(1);
+        sampler.start_sample((0, 0), 0);
+        for _ in 0..100 {
+            let Some(output) = rough.shade(input, &mut sampler) else { continue };
+            let wi = output.scatter.dir.normalized();
+            let expected = eval(&rough, wi) / rough.pdf(input, wi).unwrap();
+            assert!((output.attenuation - expected).length() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn dispersion_and_thin_film() {
+        use microfacet::{ fresnel_dielectric, fresnel_thin_film };
+
+        // crown glass is about 1.5168 at the sodium d-line, and bends blue more than red.
+        let bk7 = Ior::preset("bk7").unwrap();
+        assert!((bk7.at(587.6) - 1.5168).abs() < 1e-4);
+        assert!(bk7.at(450.0) > bk7.at(650.0));
+        let cauchy = Ior::Cauchy { a: 1.5, b: 0.0 };
+        assert_eq!(cauchy.at(400.0), cauchy.at(700.0));
+
+        // films without thickness are invisible, and reflectance depends on wavelength otherwise.
+        for cos in [1.0, 0.6, 0.2] {
+            let r = fresnel_thin_film(cos, 1.0, 1.33, 1.5, 0.0, 550.0);
+            assert!((r - fresnel_dielectric(cos, 1.5)).abs() < 1e-9);
+        }
+        let film = |lambda| fresnel_thin_film(1.0, 1.0, 1.33, 1.0, 250.0, lambda);
+        assert!((film(450.0) - film(650.0)).abs() > 0.01);
+
+        // dispersive glass restricts paths to a wavelength, but not paths already restricted.
+        let input = input(true, Vec3::new(0.3, 0.0, -1.0));
+        let glass = Dielectric::new(bk7);
+        let mut sampler = IndependentSampler::new(4);
+        sampler.start_sample((0, 0), 0);
+        let output = glass.shade(input, &mut sampler).unwrap();
+        assert!(output.wavelength.is_some());
+
+        let restricted = MatInput { wavelength: Some(500.0), ..input };
+        let output = glass.shade(restricted, &mut sampler).unwrap();
+        assert!(output.wavelength.is_none());
+        assert_eq!(output.attenuation, Vec3::from_scalar(1.0));
+    }
+}
@@ -0,0 +1,53 @@
+use std::rc::Rc;
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Vec2, Vec3, TexParam
+};
+
+/// Perturb the shading normal of another material by the gradient of a height map.
+pub struct BumpMap {
+    pub inner: Rc<dyn Material>,
+    /// Height along the outward normal, whose first channel is used.
+    pub height: TexParam,
+    /// Scale of height, per unit of `uv`.
+    pub scale: f64
+}
+
+impl BumpMap {
+    /// The offset of finite difference, in `uv` (and world space for solid textures).
+    const DELTA: f64 = 1e-3;
+
+    pub fn new(inner: Rc<dyn Material>, height: impl Into<TexParam>, scale: f64) -> BumpMap {
+        BumpMap { inner, height: height.into(), scale }
+    }
+
+    fn perturb(&self, input: MatInput) -> MatInput {
+        let (uv, p) = (input.uv, input.hitted_position);
+        let h = self.height.evaluate_scalar(uv, p);
+        let du = self.height.evaluate_scalar(uv + Vec2::new(Self::DELTA, 0.0), p + input.tangent * Self::DELTA) - h;
+        let dv = self.height.evaluate_scalar(uv + Vec2::new(0.0, Self::DELTA), p + input.bitangent * Self::DELTA) - h;
+
+        // height goes along the outward normal, which is flipped on back faces.
+        let sign = if input.surface_front { 1.0 } else { -1.0 };
+        let gradient = (input.tangent * du + input.bitangent * dv) * (self.scale / Self::DELTA);
+
+        input.with_normal(input.surface_norm - gradient * sign)
+    }
+}
+
+impl Material for BumpMap {
+    fn emissive(&self, input: MatInput) -> Vec3 {
+        self.inner.emissive(self.perturb(input))
+    }
+
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        self.inner.shade(self.perturb(input), sampler)
+    }
+
+    fn eval(&self, input: MatInput, wi: Vec3) -> Option<Vec3> {
+        self.inner.eval(self.perturb(input), wi)
+    }
+
+    fn pdf(&self, input: MatInput, wi: Vec3) -> Option<f64> {
+        self.inner.pdf(self.perturb(input), wi)
+    }
+}
@@ -0,0 +1,63 @@
+use std::rc::Rc;
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Ray, Vec3, TexParam
+};
+use super::microfacet::{ Ggx, reflect, fresnel_dielectric };
+
+/// Dielectric layer coated over another material, like varnish.
+///
+/// Rays reflect off the coat by its Fresnel reflectance, or pass through to the base
+/// and get tinted by the coat twice.
+pub struct Coated {
+    pub base: Rc<dyn Material>,
+    /// IOR of the coat.
+    pub ior: f64,
+    /// Perceptual roughness of the coat.
+    pub roughness: TexParam,
+    /// Transmittance of the coat for a single pass.
+    pub tint: TexParam
+}
+
+impl Coated {
+    pub fn new(base: Rc<dyn Material>, ior: f64, roughness: impl Into<TexParam>) -> Coated {
+        Coated { base, ior, roughness: roughness.into(), tint: TexParam::from(1.0) }
+    }
+}
+
+impl Material for Coated {
+    fn emissive(&self, input: MatInput) -> Vec3 {
+        self.base.emissive(input) * self.tint.evaluate(input.uv, input.hitted_position)
+    }
+
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        // the coat covers the front face only.
+        if !input.surface_front {
+            return self.base.shade(input, sampler);
+        }
+
+        let roughness = self.roughness.evaluate_scalar(input.uv, input.hitted_position);
+        let ggx = Ggx::from_roughness(roughness, roughness);
+
+        let wo = input.to_local(-input.incident_ray.dir.normalized());
+        if wo.z <= 0.0 { return None; }
+
+        let m = ggx.sample_visible(wo, sampler.next_2d());
+        if sampler.next_1d() >= fresnel_dielectric(wo.dot(m), self.ior) {
+            let tint = self.tint.evaluate(input.uv, input.hitted_position);
+            let output = self.base.shade(input, sampler)?;
+            return Some(ShadeOutput { attenuation: output.attenuation * tint * tint, ..output });
+        }
+
+        let wi = reflect(wo, m);
+        let scatter_dir = input.to_world(wi);
+        if wi.z <= 0.0 || scatter_dir.dot(input.geometric_norm) <= 0.0 {
+            return None;
+        }
+
+        Some(ShadeOutput {
+            scatter: Ray::new(input.hitted_position, scatter_dir),
+            attenuation: Vec3::from_scalar(ggx.g2(wo, wi) / ggx.g1(wo)),
+            wavelength: None
+        })
+    }
+}
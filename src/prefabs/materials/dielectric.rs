@@ -0,0 +1,100 @@
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Ray, Vec3,
+    math::spectrum::{ sample_wavelength, wavelength_to_rgb }
+};
+use super::microfacet::{ reflect, refract, fresnel_dielectric };
+
+/// Index of refraction, which may depend on wavelength.
+#[derive(Debug, Clone, Copy)]
+pub enum Ior {
+    Constant(f64),
+    /// *Cauchy*'s equation `n = a + b / lambda^2`, where `lambda` is in micrometers.
+    Cauchy { a: f64, b: f64 },
+    /// *Sellmeier* equation `n^2 = 1 + sum(b * lambda^2 / (lambda^2 - c))`, where `lambda` is in micrometers.
+    Sellmeier { b: [f64; 3], c: [f64; 3] }
+}
+
+impl Ior {
+    /// Get glass preset, which is `bk7` (crown glass) or `sf11` (dense flint glass).
+    pub fn preset(name: &str) -> Option<Ior> {
+        match name {
+            "bk7" => Some(Ior::Sellmeier {
+                b: [1.03961212, 0.231792344, 1.01046945],
+                c: [0.00600069867, 0.0200179144, 103.560653]
+            }),
+            "sf11" => Some(Ior::Sellmeier {
+                b: [1.73759695, 0.313747346, 1.89878101],
+                c: [0.013188707, 0.0623068142, 155.23629]
+            }),
+            _ => None
+        }
+    }
+
+    /// Whether IOR varies with wavelength.
+    pub fn is_dispersive(&self) -> bool {
+        !matches!(self, Ior::Constant(_))
+    }
+
+    /// Get IOR at wavelength `lambda` in nanometers.
+    pub fn at(&self, lambda: f64) -> f64 {
+        let l2 = (lambda * 1e-3).powi(2);
+        match *self {
+            Ior::Constant(n) => n,
+            Ior::Cauchy { a, b } => a + b / l2,
+            Ior::Sellmeier { b, c } => {
+                (1.0 + (0..3).map(|i| b[i] * l2 / (l2 - c[i])).sum::<f64>()).sqrt()
+            }
+        }
+    }
+}
+
+/// Smooth glass-like material, which disperses light if its IOR varies with wavelength.
+///
+/// Dispersive glass restricts the path to a sampled wavelength, weighted by its RGB color.
+pub struct Dielectric {
+    /// IOR of the inside, where the outside is vacuum.
+    pub ior: Ior
+}
+
+impl Dielectric {
+    /// The wavelength where IOR is evaluated, if the path carries all RGB channels.
+    const REFERENCE_WAVELENGTH: f64 = 550.0;
+
+    pub fn new(ior: Ior) -> Dielectric {
+        Dielectric { ior }
+    }
+}
+
+impl Material for Dielectric {
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        let (lambda, selected, weight) = match input.wavelength {
+            Some(lambda) => (lambda, None, Vec3::from_scalar(1.0)),
+            None if self.ior.is_dispersive() => {
+                let lambda = sample_wavelength(sampler.next_1d());
+                (lambda, Some(lambda), wavelength_to_rgb(lambda))
+            },
+            None => (Self::REFERENCE_WAVELENGTH, None, Vec3::from_scalar(1.0))
+        };
+
+        let n = self.ior.at(lambda);
+        let eta = if input.surface_front { n } else { 1.0 / n };
+
+        let wo = input.to_local(-input.incident_ray.dir.normalized());
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+        let wi = match refract(wo, normal, eta) {
+            Some(wt) if sampler.next_1d() >= fresnel_dielectric(wo.z, eta) => wt,
+            _ => reflect(wo, normal)
+        };
+
+        let scatter_dir = input.to_world(wi);
+        if (wi.z > 0.0) != (scatter_dir.dot(input.geometric_norm) > 0.0) {
+            return None;
+        }
+
+        Some(ShadeOutput {
+            scatter: Ray::new(input.hitted_position, scatter_dir),
+            attenuation: weight,
+            wavelength: selected
+        })
+    }
+}
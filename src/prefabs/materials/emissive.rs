@@ -1,23 +1,23 @@
 use super::super::super::core::{
-    Material, MatInput, ShadeOutput, Vec3
+    Material, MatInput, ShadeOutput, Sampler, Vec3, TexParam
 };
 
 pub struct Emissive {
-    pub emissive: Vec3
+    pub emissive: TexParam
 }
 
 impl Emissive {
-    pub fn new(emissive: Vec3) -> Emissive {
-        Emissive { emissive }
+    pub fn new(emissive: impl Into<TexParam>) -> Emissive {
+        Emissive { emissive: emissive.into() }
     }
 }
 
 impl Material for Emissive {
-    fn emissive(&self, _input: MatInput) -> Vec3 {
-        self.emissive
+    fn emissive(&self, input: MatInput) -> Vec3 {
+        self.emissive.evaluate(input.uv, input.hitted_position)
     }
 
-    fn shade(&self, _input: MatInput) -> Option<ShadeOutput> {
+    fn shade(&self, _input: MatInput, _sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
         None
     }
 }
\ No newline at end of file
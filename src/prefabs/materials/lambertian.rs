@@ -1,31 +1,50 @@
+use std::f64::consts::PI;
 use super::super::super::core::{
-    Material, MatInput, ShadeOutput, Ray, Vec3
+    Material, MatInput, ShadeOutput, Sampler, Ray, Vec3, TexParam,
+    math::utils::uniform_sphere
 };
 
 pub struct Lambertian {
-    pub albedo: Vec3
+    pub albedo: TexParam
 }
 
 impl Lambertian {
-    pub fn new(albedo: Vec3) -> Lambertian {
-        Lambertian { albedo }
+    pub fn new(albedo: impl Into<TexParam>) -> Lambertian {
+        Lambertian { albedo: albedo.into() }
     }
 }
 
 impl Material for Lambertian {
-    fn shade(&self, input: MatInput) -> Option<ShadeOutput> {
-        let mut scatter_dir = input.surface_norm + Vec3::random_unit();
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        let mut scatter_dir = input.surface_norm + uniform_sphere(sampler.next_2d());
         
         // prevent scatter_dir nearly equal vec3(0.0).
         if scatter_dir.length_square() < 3e-16 {
             scatter_dir = input.surface_norm;
         }
 
+        // the shading normal may lean so far that rays go under the actual surface.
+        if scatter_dir.dot(input.geometric_norm) <= 0.0 {
+            return None;
+        }
+
         let scatter = Ray::new(input.hitted_position, scatter_dir);
 
         Some(ShadeOutput {
             scatter, 
-            attenuation: self.albedo
+            attenuation: self.albedo.evaluate(input.uv, input.hitted_position),
+            wavelength: None
         })
     }
-}
\ No newline at end of file
+
+    fn eval(&self, input: MatInput, wi: Vec3) -> Option<Vec3> {
+        if wi.dot(input.geometric_norm) <= 0.0 {
+            return Some(Vec3::from_scalar(0.0));
+        }
+        Some(self.albedo.evaluate(input.uv, input.hitted_position) * self.pdf(input, wi)?)
+    }
+
+    fn pdf(&self, input: MatInput, wi: Vec3) -> Option<f64> {
+        Some(wi.dot(input.surface_norm).max(0.0) / PI)
+    }
+}
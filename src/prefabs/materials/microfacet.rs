@@ -0,0 +1,151 @@
+//! Shared pieces of microfacet materials, whose vectors are in the local shading frame
+//! where `z` is the shading normal.
+use std::f64::consts::PI;
+use super::super::super::core::{ Vec2, Vec3 };
+
+/// *GGX* (*Trowbridge-Reitz*) distribution of microfacet normals.
+#[derive(Debug, Clone, Copy)]
+pub struct Ggx {
+    /// Roughness along the tangent.
+    pub alpha_x: f64,
+    /// Roughness along the bitangent.
+    pub alpha_y: f64
+}
+
+impl Ggx {
+    /// Create distribution from perceptual roughness in `[0, 1]`, where `alpha = roughness^2`.
+    pub fn from_roughness(roughness_u: f64, roughness_v: f64) -> Ggx {
+        let alpha = |r: f64| (r.clamp(0.0, 1.0) * r.clamp(0.0, 1.0)).max(1e-4);
+        Ggx { alpha_x: alpha(roughness_u), alpha_y: alpha(roughness_v) }
+    }
+
+    /// Density of microfacet normal `m`.
+    pub fn d(&self, m: Vec3) -> f64 {
+        if m.z <= 0.0 { return 0.0; }
+        let e = (m.x / self.alpha_x).powi(2) + (m.y / self.alpha_y).powi(2) + m.z * m.z;
+        1.0 / (PI * self.alpha_x * self.alpha_y * e * e)
+    }
+
+    /// *Smith* auxiliary function, where `G1(w) = 1 / (1 + lambda(w))`.
+    pub fn lambda(&self, w: Vec3) -> f64 {
+        if w.z == 0.0 { return f64::INFINITY; }
+        let tan2 = ((self.alpha_x * w.x).powi(2) + (self.alpha_y * w.y).powi(2)) / (w.z * w.z);
+        0.5 * ((1.0 + tan2).sqrt() - 1.0)
+    }
+
+    /// Masking of direction `w`.
+    pub fn g1(&self, w: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(w))
+    }
+
+    /// Height-correlated masking-shadowing of `wo` and `wi`.
+    pub fn g2(&self, wo: Vec3, wi: Vec3) -> f64 {
+        1.0 / (1.0 + self.lambda(wo) + self.lambda(wi))
+    }
+
+    /// Sample a microfacet normal visible from `wo` (`wo.z > 0`).
+    ///
+    /// *Heitz*, "Sampling the GGX Distribution of Visible Normals".
+    /// Scattering around the normal weighs `G2 / G1(wo)` times the Fresnel term.
+    pub fn sample_visible(&self, wo: Vec3, u: Vec2) -> Vec3 {
+        let vh = Vec3::new(self.alpha_x * wo.x, self.alpha_y * wo.y, wo.z).normalized();
+
+        let len2 = vh.x * vh.x + vh.y * vh.y;
+        let t1 = if len2 > 0.0 { Vec3::new(-vh.y, vh.x, 0.0) / len2.sqrt() } else { Vec3::new(1.0, 0.0, 0.0) };
+        let t2 = vh.cross(t1);
+
+        let r = u.x.sqrt();
+        let phi = 2.0 * PI * u.y;
+        let p1 = r * phi.cos();
+        let s = 0.5 * (1.0 + vh.z);
+        let p2 = (1.0 - s) * (1.0 - p1 * p1).max(0.0).sqrt() + s * r * phi.sin();
+
+        let nh = t1 * p1 + t2 * p2 + vh * (1.0 - p1 * p1 - p2 * p2).max(0.0).sqrt();
+        Vec3::new(self.alpha_x * nh.x, self.alpha_y * nh.y, nh.z.max(1e-6)).normalized()
+    }
+}
+
+/// Reflect `w` about `m`, both pointing away from surface.
+pub fn reflect(w: Vec3, m: Vec3) -> Vec3 {
+    m * (2.0 * w.dot(m)) - w
+}
+
+/// Refract `w` through `m`, both on the same side, where `eta` is the relative IOR of the other side.
+///
+/// Return `None` on total internal reflection.
+pub fn refract(w: Vec3, m: Vec3, eta: f64) -> Option<Vec3> {
+    let cos_i = w.dot(m);
+    let sin2_t = (1.0 - cos_i * cos_i).max(0.0) / (eta * eta);
+    if sin2_t >= 1.0 { return None; }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    Some(-w / eta + m * (cos_i / eta - cos_t))
+}
+
+/// Unpolarized Fresnel reflectance of dielectric, where `eta` is the relative IOR of the other side.
+pub fn fresnel_dielectric(cos_i: f64, eta: f64) -> f64 {
+    let cos_i = cos_i.clamp(0.0, 1.0);
+    let sin2_t = (1.0 - cos_i * cos_i) / (eta * eta);
+    if sin2_t >= 1.0 { return 1.0; }
+
+    let cos_t = (1.0 - sin2_t).sqrt();
+    let rs = (cos_i - eta * cos_t) / (cos_i + eta * cos_t);
+    let rp = (eta * cos_i - cos_t) / (eta * cos_i + cos_t);
+    0.5 * (rs * rs + rp * rp)
+}
+
+/// Unpolarized Fresnel reflectance of conductor with complex IOR `eta + i k`, per channel.
+pub fn fresnel_conductor(cos_i: f64, eta: Vec3, k: Vec3) -> Vec3 {
+    let channel = |eta: f64, k: f64| -> f64 {
+        let cos2 = cos_i.clamp(0.0, 1.0).powi(2);
+        let sin2 = 1.0 - cos2;
+        let t0 = eta * eta - k * k - sin2;
+        let a2b2 = (t0 * t0 + 4.0 * eta * eta * k * k).sqrt();
+        let a = (0.5 * (a2b2 + t0)).max(0.0).sqrt();
+        let cos = cos2.sqrt();
+
+        let rs = (a2b2 + cos2 - 2.0 * a * cos) / (a2b2 + cos2 + 2.0 * a * cos);
+        let t1 = cos2 * a2b2 + sin2 * sin2;
+        let t2 = 2.0 * a * cos * sin2;
+        let rp = rs * (t1 - t2) / (t1 + t2);
+        0.5 * (rs + rp)
+    };
+
+    Vec3::new(channel(eta.x, k.x), channel(eta.y, k.y), channel(eta.z, k.z))
+}
+
+/// *Schlick*'s approximation of Fresnel reflectance, from reflectance `f0` at normal incidence.
+pub fn fresnel_schlick(cos_i: f64, f0: Vec3) -> Vec3 {
+    let w = (1.0 - cos_i.clamp(0.0, 1.0)).powi(5);
+    f0 + (Vec3::from_scalar(1.0) - f0) * w
+}
+
+/// Unpolarized reflectance of a thin film between two media, by interference of its two interfaces.
+///
+/// - `n1`, `n2` and `n3` the IORs of the incident medium, the film and the medium behind.
+/// - `thickness` and `lambda` the thickness of film and the wavelength, in nanometers.
+pub fn fresnel_thin_film(cos_i: f64, n1: f64, n2: f64, n3: f64, thickness: f64, lambda: f64) -> f64 {
+    let cos1 = cos_i.clamp(0.0, 1.0);
+    let sin2_1 = 1.0 - cos1 * cos1;
+    let sin2_2 = sin2_1 * (n1 / n2).powi(2);
+    let sin2_3 = sin2_1 * (n1 / n3).powi(2);
+    if sin2_2 >= 1.0 || sin2_3 >= 1.0 { return 1.0; }
+    let (cos2, cos3) = ((1.0 - sin2_2).sqrt(), (1.0 - sin2_3).sqrt());
+
+    // *Airy* summation of multiple reflections inside the film.
+    let cos_delta = (4.0 * PI * n2 * thickness * cos2 / lambda).cos();
+    let airy = |r12: f64, r23: f64| -> f64 {
+        let cross = 2.0 * r12 * r23 * cos_delta;
+        (r12 * r12 + r23 * r23 + cross) / (1.0 + r12 * r12 * r23 * r23 + cross)
+    };
+
+    let rs = airy(
+        (n1 * cos1 - n2 * cos2) / (n1 * cos1 + n2 * cos2),
+        (n2 * cos2 - n3 * cos3) / (n2 * cos2 + n3 * cos3)
+    );
+    let rp = airy(
+        (n2 * cos1 - n1 * cos2) / (n2 * cos1 + n1 * cos2),
+        (n3 * cos2 - n2 * cos3) / (n3 * cos2 + n2 * cos3)
+    );
+    0.5 * (rs + rp)
+}
@@ -0,0 +1,51 @@
+use std::rc::Rc;
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Vec3, TexParam
+};
+
+/// Blend of two materials, which picks one of them stochastically for every shading.
+pub struct Mix {
+    /// Material where `weight` is 0.
+    pub first: Rc<dyn Material>,
+    /// Material where `weight` is 1.
+    pub second: Rc<dyn Material>,
+    /// Blend factor in `[0, 1]`, whose first channel is used.
+    pub weight: TexParam
+}
+
+impl Mix {
+    pub fn new(first: Rc<dyn Material>, second: Rc<dyn Material>, weight: impl Into<TexParam>) -> Mix {
+        Mix { first, second, weight: weight.into() }
+    }
+
+    fn weight(&self, input: &MatInput) -> f64 {
+        self.weight.evaluate_scalar(input.uv, input.hitted_position).clamp(0.0, 1.0)
+    }
+}
+
+impl Material for Mix {
+    fn emissive(&self, input: MatInput) -> Vec3 {
+        let w = self.weight(&input);
+        self.first.emissive(input) * (1.0 - w) + self.second.emissive(input) * w
+    }
+
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        if sampler.next_1d() < self.weight(&input) {
+            self.second.shade(input, sampler)
+        } else {
+            self.first.shade(input, sampler)
+        }
+    }
+
+    /// Only available if both materials implement it.
+    fn eval(&self, input: MatInput, wi: Vec3) -> Option<Vec3> {
+        let w = self.weight(&input);
+        Some(self.first.eval(input, wi)? * (1.0 - w) + self.second.eval(input, wi)? * w)
+    }
+
+    /// Only available if both materials implement it.
+    fn pdf(&self, input: MatInput, wi: Vec3) -> Option<f64> {
+        let w = self.weight(&input);
+        Some(self.first.pdf(input, wi)? * (1.0 - w) + self.second.pdf(input, wi)? * w)
+    }
+}
@@ -0,0 +1,48 @@
+use std::rc::Rc;
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Vec3, TexParam
+};
+
+/// Perturb the shading normal of another material by a tangent-space normal map.
+///
+/// Texels store `(x, y, z)` of the normal in `[0, 1]`, where `x` points along the tangent,
+/// `y` along the bitangent and `z` along the surface normal. Image maps must be loaded without sRGB decoding.
+pub struct NormalMap {
+    pub inner: Rc<dyn Material>,
+    pub map: TexParam,
+    /// Scale of the tangential part, where `0` keeps the surface flat.
+    pub strength: f64
+}
+
+impl NormalMap {
+    pub fn new(inner: Rc<dyn Material>, map: impl Into<TexParam>, strength: f64) -> NormalMap {
+        NormalMap { inner, map: map.into(), strength }
+    }
+
+    fn perturb(&self, input: MatInput) -> MatInput {
+        let texel = self.map.evaluate(input.uv, input.hitted_position) * 2.0 - 1.0;
+        // the map describes the outward normal, which is flipped on back faces.
+        let tilt = if input.surface_front { self.strength } else { -self.strength };
+        let local = Vec3::new(texel.x * tilt, texel.y * tilt, texel.z.max(1e-3));
+
+        input.with_normal(input.to_world(local))
+    }
+}
+
+impl Material for NormalMap {
+    fn emissive(&self, input: MatInput) -> Vec3 {
+        self.inner.emissive(self.perturb(input))
+    }
+
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        self.inner.shade(self.perturb(input), sampler)
+    }
+
+    fn eval(&self, input: MatInput, wi: Vec3) -> Option<Vec3> {
+        self.inner.eval(self.perturb(input), wi)
+    }
+
+    fn pdf(&self, input: MatInput, wi: Vec3) -> Option<f64> {
+        self.inner.pdf(self.perturb(input), wi)
+    }
+}
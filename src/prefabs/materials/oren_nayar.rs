@@ -0,0 +1,82 @@
+use std::f64::consts::PI;
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Ray, Vec3, TexParam,
+    math::utils::uniform_sphere
+};
+
+/// Rough diffuse material of *Oren–Nayar* model, for matte surfaces like clay and fabric,
+/// which are brighter toward the light than [`Lambertian`](super::Lambertian) at grazing angles.
+pub struct OrenNayar {
+    pub albedo: TexParam,
+    /// Standard deviation of microfacet slopes in radians, where `0` is *Lambertian*.
+    pub sigma: f64,
+
+    a: f64,
+    b: f64
+}
+
+impl OrenNayar {
+    pub fn new(albedo: impl Into<TexParam>, sigma: f64) -> OrenNayar {
+        let sigma2 = sigma * sigma;
+        OrenNayar {
+            albedo: albedo.into(),
+            sigma,
+            a: 1.0 - sigma2 / (2.0 * (sigma2 + 0.33)),
+            b: 0.45 * sigma2 / (sigma2 + 0.09)
+        }
+    }
+
+    /// Get the scattering function times `PI`, which is the weight of cosine-weighted sampling.
+    fn reflectance(&self, wo: Vec3, wi: Vec3) -> f64 {
+        let sin_o = (1.0 - wo.z * wo.z).max(0.0).sqrt();
+        let sin_i = (1.0 - wi.z * wi.z).max(0.0).sqrt();
+
+        // cos(phi_i - phi_o), projected onto the tangent plane.
+        let cos_phi = if sin_o > 1e-6 && sin_i > 1e-6 {
+            ((wo.x * wi.x + wo.y * wi.y) / (sin_o * sin_i)).max(0.0)
+        } else { 0.0 };
+
+        // sin(alpha) * tan(beta), where alpha and beta are the larger and smaller polar angles.
+        let (sin_alpha, tan_beta) = if wi.z.abs() > wo.z.abs() {
+            (sin_o, sin_i / wi.z.abs())
+        } else {
+            (sin_i, sin_o / wo.z.abs().max(1e-6))
+        };
+
+        self.a + self.b * cos_phi * sin_alpha * tan_beta
+    }
+}
+
+impl Material for OrenNayar {
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        let wo = input.to_local(-input.incident_ray.dir.normalized());
+        let wi = (Vec3::new(0.0, 0.0, 1.0) + uniform_sphere(sampler.next_2d())).normalized();
+
+        let scatter_dir = input.to_world(wi);
+        if wi.z <= 0.0 || scatter_dir.dot(input.geometric_norm) <= 0.0 {
+            return None;
+        }
+
+        let albedo = self.albedo.evaluate(input.uv, input.hitted_position);
+        Some(ShadeOutput {
+            scatter: Ray::new(input.hitted_position, scatter_dir),
+            attenuation: albedo * self.reflectance(wo, wi),
+            wavelength: None
+        })
+    }
+
+    fn eval(&self, input: MatInput, wi: Vec3) -> Option<Vec3> {
+        if wi.dot(input.geometric_norm) <= 0.0 {
+            return Some(Vec3::from_scalar(0.0));
+        }
+
+        let wo = input.to_local(-input.incident_ray.dir.normalized());
+        let wi_local = input.to_local(wi);
+        let albedo = self.albedo.evaluate(input.uv, input.hitted_position);
+        Some(albedo * (self.reflectance(wo, wi_local) * self.pdf(input, wi)?))
+    }
+
+    fn pdf(&self, input: MatInput, wi: Vec3) -> Option<f64> {
+        Some(wi.dot(input.surface_norm).max(0.0) / PI)
+    }
+}
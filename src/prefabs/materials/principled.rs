@@ -0,0 +1,132 @@
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Ray, Vec3, TexParam,
+    math::utils::uniform_sphere
+};
+use super::microfacet::{ Ggx, reflect, refract, fresnel_dielectric, fresnel_schlick };
+
+/// *Disney*-style principled material, which layers a clearcoat over a blend of
+/// diffuse, glossy dielectric, metal and glass.
+///
+/// Lobes are picked stochastically, so each shading scatters one ray.
+/// All parameters but `ior` are texturable, and scalars take the first channel.
+pub struct Principled {
+    /// Diffuse albedo, metal reflectance at normal incidence, and transmission tint.
+    pub base_color: TexParam,
+    /// Blend from dielectric (`0`) to metal (`1`).
+    pub metallic: TexParam,
+    /// Perceptual roughness of specular lobes.
+    pub roughness: TexParam,
+    /// Dielectric reflectance at normal incidence, where `0.5` means `4%`.
+    pub specular: TexParam,
+    /// Strength of the grazing rim on the diffuse lobe, for cloth, in `[0, 1]`.
+    pub sheen: TexParam,
+    /// Strength of a white clear layer on top.
+    pub clearcoat: TexParam,
+    /// Perceptual roughness of the clear layer.
+    pub clearcoat_roughness: TexParam,
+    /// Blend from opaque (`0`) to glass (`1`), for dielectrics.
+    pub transmission: TexParam,
+    /// IOR of transmission.
+    pub ior: f64,
+    /// Emitted radiance.
+    pub emission: TexParam
+}
+
+impl Principled {
+    /// Create a grey rough plastic, whose parameters are then set by fields.
+    pub fn new(base_color: impl Into<TexParam>) -> Principled {
+        Principled {
+            base_color: base_color.into(),
+            metallic: TexParam::from(0.0),
+            roughness: TexParam::from(0.5),
+            specular: TexParam::from(0.5),
+            sheen: TexParam::from(0.0),
+            clearcoat: TexParam::from(0.0),
+            clearcoat_roughness: TexParam::from(0.03),
+            transmission: TexParam::from(0.0),
+            ior: 1.5,
+            emission: TexParam::from(0.0)
+        }
+    }
+
+    /// Reflect about a visible microfacet normal, weighted by `fresnel`.
+    fn glossy(ggx: &Ggx, wo: Vec3, m: Vec3, fresnel: Vec3) -> Option<(Vec3, Vec3)> {
+        let wi = reflect(wo, m);
+        if wi.z <= 0.0 { return None; }
+        Some((wi, fresnel * (ggx.g2(wo, wi) / ggx.g1(wo))))
+    }
+}
+
+impl Material for Principled {
+    fn emissive(&self, input: MatInput) -> Vec3 {
+        self.emission.evaluate(input.uv, input.hitted_position)
+    }
+
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        let (uv, p) = (input.uv, input.hitted_position);
+        let base_color = self.base_color.evaluate(uv, p);
+        let metallic = self.metallic.evaluate_scalar(uv, p).clamp(0.0, 1.0);
+        let roughness = self.roughness.evaluate_scalar(uv, p);
+        let specular = self.specular.evaluate_scalar(uv, p).max(0.0);
+        let clearcoat = self.clearcoat.evaluate_scalar(uv, p).clamp(0.0, 1.0);
+        let transmission = self.transmission.evaluate_scalar(uv, p).clamp(0.0, 1.0);
+
+        let wo = input.to_local(-input.incident_ray.dir.normalized());
+        if wo.z <= 0.0 { return None; }
+        let white = Vec3::from_scalar(1.0);
+
+        // clearcoat is picked by its Fresnel reflectance, which cancels from the weight,
+        // and leaves the rest of energy to layers below.
+        let coat = clearcoat * fresnel_schlick(wo.z, Vec3::from_scalar(0.04)).x;
+        let lobe = if input.surface_front && sampler.next_1d() < coat {
+            let clearcoat_roughness = self.clearcoat_roughness.evaluate_scalar(uv, p);
+            let ggx = Ggx::from_roughness(clearcoat_roughness, clearcoat_roughness);
+            let m = ggx.sample_visible(wo, sampler.next_2d());
+            Self::glossy(&ggx, wo, m, white)
+        } else {
+            let ggx = Ggx::from_roughness(roughness, roughness);
+            let m = ggx.sample_visible(wo, sampler.next_2d());
+
+            if sampler.next_1d() < metallic {
+                Self::glossy(&ggx, wo, m, fresnel_schlick(wo.dot(m), base_color))
+            } else if sampler.next_1d() < transmission {
+                let eta = if input.surface_front { self.ior } else { 1.0 / self.ior };
+                let fresnel = fresnel_dielectric(wo.dot(m), eta);
+                match refract(wo, m, eta) {
+                    Some(wt) if sampler.next_1d() >= fresnel => {
+                        if wt.z >= 0.0 { return None; }
+                        Some((wt, base_color * (ggx.g2(wo, wt) / ggx.g1(wo))))
+                    },
+                    _ => Self::glossy(&ggx, wo, m, white)
+                }
+            } else {
+                // `specular = 0.5` is the reflectance (4%) of IOR 1.5.
+                let f0 = (0.08 * specular).min(1.0);
+                if sampler.next_1d() < fresnel_schlick(wo.dot(m), Vec3::from_scalar(f0)).x {
+                    Self::glossy(&ggx, wo, m, white)
+                } else {
+                    let wi = (Vec3::new(0.0, 0.0, 1.0) + uniform_sphere(sampler.next_2d())).normalized();
+                    if wi.z <= 0.0 { return None; }
+
+                    let half = (wi + wo).normalized();
+                    let sheen = self.sheen.evaluate_scalar(uv, p).clamp(0.0, 1.0)
+                              * (1.0 - wi.dot(half).clamp(0.0, 1.0)).powi(5);
+                    // sheen only fills the albedo left by base color, so it never gains energy.
+                    Some((wi, base_color + (white - base_color) * sheen))
+                }
+            }
+        };
+
+        let (wi, attenuation) = lobe?;
+        let scatter_dir = input.to_world(wi);
+        if (wi.z > 0.0) != (scatter_dir.dot(input.geometric_norm) > 0.0) {
+            return None;
+        }
+
+        Some(ShadeOutput {
+            scatter: Ray::new(input.hitted_position, scatter_dir),
+            attenuation,
+            wavelength: None
+        })
+    }
+}
@@ -0,0 +1,61 @@
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Ray, Vec3, TexParam
+};
+use super::microfacet::{ Ggx, reflect, fresnel_conductor };
+
+/// Metal with a *GGX* rough surface.
+pub struct RoughConductor {
+    /// Real part of complex IOR, per channel.
+    pub eta: Vec3,
+    /// Imaginary part of complex IOR, per channel.
+    pub k: Vec3,
+    /// Perceptual roughness along the tangent.
+    pub roughness_u: TexParam,
+    /// Perceptual roughness along the bitangent.
+    pub roughness_v: TexParam
+}
+
+impl RoughConductor {
+    pub fn new(eta: Vec3, k: Vec3, roughness_u: impl Into<TexParam>, roughness_v: impl Into<TexParam>) -> RoughConductor {
+        RoughConductor { eta, k, roughness_u: roughness_u.into(), roughness_v: roughness_v.into() }
+    }
+
+    /// Get complex IOR `(eta, k)` of a metal preset, at the red, green and blue wavelengths.
+    ///
+    /// Presets are `gold`, `copper`, `aluminium` and `silver`.
+    pub fn preset(name: &str) -> Option<(Vec3, Vec3)> {
+        match name {
+            "gold" => Some((Vec3::new(0.143, 0.374, 1.442), Vec3::new(3.983, 2.385, 1.603))),
+            "copper" => Some((Vec3::new(0.200, 0.924, 1.102), Vec3::new(3.912, 2.452, 2.142))),
+            "aluminium" => Some((Vec3::new(1.657, 0.880, 0.521), Vec3::new(9.224, 6.270, 4.837))),
+            "silver" => Some((Vec3::new(0.155, 0.117, 0.138), Vec3::new(4.828, 3.122, 2.147))),
+            _ => None
+        }
+    }
+}
+
+impl Material for RoughConductor {
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        let ggx = Ggx::from_roughness(
+            self.roughness_u.evaluate_scalar(input.uv, input.hitted_position),
+            self.roughness_v.evaluate_scalar(input.uv, input.hitted_position)
+        );
+
+        let wo = input.to_local(-input.incident_ray.dir.normalized());
+        if wo.z <= 0.0 { return None; }
+
+        let m = ggx.sample_visible(wo, sampler.next_2d());
+        let wi = reflect(wo, m);
+        let scatter_dir = input.to_world(wi);
+        if wi.z <= 0.0 || scatter_dir.dot(input.geometric_norm) <= 0.0 {
+            return None;
+        }
+
+        let fresnel = fresnel_conductor(wo.dot(m), self.eta, self.k);
+        Some(ShadeOutput {
+            scatter: Ray::new(input.hitted_position, scatter_dir),
+            attenuation: fresnel * (ggx.g2(wo, wi) / ggx.g1(wo)),
+            wavelength: None
+        })
+    }
+}
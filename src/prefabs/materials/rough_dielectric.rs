@@ -0,0 +1,57 @@
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Ray, Vec3, TexParam
+};
+use super::microfacet::{ Ggx, reflect, refract, fresnel_dielectric };
+
+/// Glass-like material with a *GGX* rough surface,
+/// which reflects or transmits by Fresnel reflectance.
+pub struct RoughDielectric {
+    /// IOR of the inside, where the outside is vacuum.
+    pub ior: f64,
+    /// Perceptual roughness along the tangent.
+    pub roughness_u: TexParam,
+    /// Perceptual roughness along the bitangent.
+    pub roughness_v: TexParam
+}
+
+impl RoughDielectric {
+    pub fn new(ior: f64, roughness_u: impl Into<TexParam>, roughness_v: impl Into<TexParam>) -> RoughDielectric {
+        RoughDielectric { ior, roughness_u: roughness_u.into(), roughness_v: roughness_v.into() }
+    }
+}
+
+impl Material for RoughDielectric {
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        let ggx = Ggx::from_roughness(
+            self.roughness_u.evaluate_scalar(input.uv, input.hitted_position),
+            self.roughness_v.evaluate_scalar(input.uv, input.hitted_position)
+        );
+        // the relative IOR of the side which rays transmit into.
+        let eta = if input.surface_front { self.ior } else { 1.0 / self.ior };
+
+        let wo = input.to_local(-input.incident_ray.dir.normalized());
+        if wo.z <= 0.0 { return None; }
+
+        let m = ggx.sample_visible(wo, sampler.next_2d());
+        let fresnel = fresnel_dielectric(wo.dot(m), eta);
+
+        // choosing between reflection and transmission by Fresnel cancels it from the weight.
+        let (wi, is_reflect) = match refract(wo, m, eta) {
+            Some(wt) if sampler.next_1d() >= fresnel => (wt, false),
+            _ => (reflect(wo, m), true)
+        };
+        let scatter_dir = input.to_world(wi);
+
+        // reflected rays must stay outside, and transmitted rays go inside,
+        // with respect to both the microfacet's macro surface and the actual surface.
+        if (wi.z > 0.0) != is_reflect || (scatter_dir.dot(input.geometric_norm) > 0.0) != is_reflect {
+            return None;
+        }
+
+        Some(ShadeOutput {
+            scatter: Ray::new(input.hitted_position, scatter_dir),
+            attenuation: Vec3::from_scalar(ggx.g2(wo, wi) / ggx.g1(wo)),
+            wavelength: None
+        })
+    }
+}
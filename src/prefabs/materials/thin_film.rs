@@ -0,0 +1,59 @@
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Ray, Vec3, TexParam,
+    math::spectrum::RGB_WAVELENGTHS
+};
+use super::microfacet::{ reflect, refract, fresnel_thin_film };
+
+/// Smooth surface coated by a thin film, whose reflection is iridescent by interference,
+/// like soap bubbles, oil slicks and coated lenses.
+///
+/// Reflectance is evaluated at the wavelength of path if it is restricted,
+/// otherwise at the wavelengths representing RGB channels.
+pub struct ThinFilm {
+    /// Thickness of the film in nanometers, whose first channel is used.
+    pub thickness: TexParam,
+    /// IOR of the film.
+    pub film_ior: f64,
+    /// IOR of the inside, where `1` is a bubble of film and the outside is vacuum.
+    pub ior: f64
+}
+
+impl ThinFilm {
+    pub fn new(thickness: impl Into<TexParam>, film_ior: f64, ior: f64) -> ThinFilm {
+        ThinFilm { thickness: thickness.into(), film_ior, ior }
+    }
+}
+
+impl Material for ThinFilm {
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        let thickness = self.thickness.evaluate_scalar(input.uv, input.hitted_position).max(0.0);
+        let (n1, n3) = if input.surface_front { (1.0, self.ior) } else { (self.ior, 1.0) };
+
+        let wo = input.to_local(-input.incident_ray.dir.normalized());
+        let film = |lambda: f64| fresnel_thin_film(wo.z, n1, self.film_ior, n3, thickness, lambda);
+        let reflectance = match input.wavelength {
+            Some(lambda) => Vec3::from_scalar(film(lambda)),
+            None => Vec3::new(film(RGB_WAVELENGTHS[0]), film(RGB_WAVELENGTHS[1]), film(RGB_WAVELENGTHS[2]))
+        };
+
+        // pick reflection by the mean reflectance, and weight channels by their own.
+        let normal = Vec3::new(0.0, 0.0, 1.0);
+        let p = (reflectance.x + reflectance.y + reflectance.z) / 3.0;
+        let (wi, attenuation) = match refract(wo, normal, n3 / n1) {
+            Some(wt) if sampler.next_1d() >= p => (wt, (Vec3::from_scalar(1.0) - reflectance) / (1.0 - p)),
+            Some(_) => (reflect(wo, normal), reflectance / p),
+            None => (reflect(wo, normal), Vec3::from_scalar(1.0))
+        };
+
+        let scatter_dir = input.to_world(wi);
+        if (wi.z > 0.0) != (scatter_dir.dot(input.geometric_norm) > 0.0) {
+            return None;
+        }
+
+        Some(ShadeOutput {
+            scatter: Ray::new(input.hitted_position, scatter_dir),
+            attenuation,
+            wavelength: None
+        })
+    }
+}
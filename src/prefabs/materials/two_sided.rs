@@ -0,0 +1,38 @@
+use std::rc::Rc;
+use super::super::super::core::{
+    Material, MatInput, ShadeOutput, Sampler, Vec3
+};
+
+/// Different materials on the front and back faces, like leaves and paper.
+pub struct TwoSided {
+    pub front: Rc<dyn Material>,
+    pub back: Rc<dyn Material>
+}
+
+impl TwoSided {
+    pub fn new(front: Rc<dyn Material>, back: Rc<dyn Material>) -> TwoSided {
+        TwoSided { front, back }
+    }
+
+    fn side(&self, input: &MatInput) -> &dyn Material {
+        if input.surface_front { self.front.as_ref() } else { self.back.as_ref() }
+    }
+}
+
+impl Material for TwoSided {
+    fn emissive(&self, input: MatInput) -> Vec3 {
+        self.side(&input).emissive(input)
+    }
+
+    fn shade(&self, input: MatInput, sampler: &mut dyn Sampler) -> Option<ShadeOutput> {
+        self.side(&input).shade(input, sampler)
+    }
+
+    fn eval(&self, input: MatInput, wi: Vec3) -> Option<Vec3> {
+        self.side(&input).eval(input, wi)
+    }
+
+    fn pdf(&self, input: MatInput, wi: Vec3) -> Option<f64> {
+        self.side(&input).pdf(input, wi)
+    }
+}
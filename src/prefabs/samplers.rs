@@ -0,0 +1,77 @@
+pub mod independent;
+pub use independent::IndependentSampler;
+
+pub mod stratified;
+pub use stratified::StratifiedSampler;
+
+pub mod halton;
+pub use halton::HaltonSampler;
+
+pub mod sobol;
+pub use sobol::SobolSampler;
+
+#[cfg(test)]
+mod samplers_tests {
+    use super::*;
+    use crate::core::Sampler;
+
+    const SPP: u32 = 64;
+
+    fn samplers() -> Vec<Box<dyn Sampler>> {
+        vec![
+            Box::new(IndependentSampler::new(7)),
+            Box::new(StratifiedSampler::new(SPP, 7)),
+            Box::new(HaltonSampler::new(7)),
+            Box::new(SobolSampler::new(7))
+        ]
+    }
+
+    #[test]
+    fn in_unit_range() {
+        for mut sampler in samplers() {
+            for index in 0..SPP {
+                sampler.start_sample((3, 5), index);
+                for _ in 0..8 {
+                    let u = sampler.next_2d();
+                    let v = sampler.next_1d();
+                    assert!((0.0..1.0).contains(&u.x) && (0.0..1.0).contains(&u.y));
+                    assert!((0.0..1.0).contains(&v));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn reproducible() {
+        for mut sampler in samplers() {
+            sampler.start_sample((1, 2), 9);
+            let first = (sampler.next_2d(), sampler.next_1d());
+
+            sampler.start_sample((4, 4), 3);
+            sampler.next_2d();
+
+            sampler.start_sample((1, 2), 9);
+            assert_eq!((sampler.next_2d(), sampler.next_1d()), first);
+        }
+    }
+
+    #[test]
+    fn stratified_2d() {
+        // every 8x8 stratum of the first 2D dimension is hit exactly once.
+        let samplers: Vec<Box<dyn Sampler>> = vec![
+            Box::new(StratifiedSampler::new(SPP, 7)),
+            Box::new(SobolSampler::new(7))
+        ];
+
+        for mut sampler in samplers {
+            let mut strata = [0; 64];
+            for index in 0..SPP {
+                sampler.start_sample((2, 7), index);
+                let u = sampler.next_2d();
+                strata[(u.y * 8.0) as usize * 8 + (u.x * 8.0) as usize] += 1;
+            }
+
+            assert!(strata.iter().all(|n| *n == 1));
+        }
+    }
+}
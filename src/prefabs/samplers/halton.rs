@@ -0,0 +1,83 @@
+use super::super::super::core::{ Sampler, Vec2, math::utils::hash };
+
+/// Halton sampler.
+/// 
+/// Dimension `d` is the radical inverse of sample index in the `d`-th prime base,
+/// randomized per pixel by Cranley-Patterson rotation.
+/// Dimensions beyond the prime table fall back to random values.
+#[derive(Clone)]
+pub struct HaltonSampler {
+    pub seed: u64,
+
+    // Current sample state.
+    pixel: (u32, u32),
+    index: u32,
+    dimension: u32
+}
+
+impl HaltonSampler {
+    pub fn new(seed: u64) -> HaltonSampler {
+        HaltonSampler { seed, pixel: (0, 0), index: 0, dimension: 0 }
+    }
+
+    fn sample_dimension(&self, dimension: u32) -> f64 {
+        let key = hash(&[self.seed, self.pixel.0 as u64, self.pixel.1 as u64, dimension as u64]);
+        let rotation = (key >> 11) as f64 / (1u64 << 53) as f64;
+
+        let value = match PRIMES.get(dimension as usize) {
+            Some(base) => radical_inverse(*base, self.index),
+            None => (hash(&[key, self.index as u64]) >> 11) as f64 / (1u64 << 53) as f64
+        };
+
+        let rotated = value + rotation;
+        (rotated - rotated.floor()).min(1.0 - f64::EPSILON / 2.0)
+    }
+}
+
+impl Sampler for HaltonSampler {
+    fn start_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.pixel = pixel;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        let value = self.sample_dimension(self.dimension);
+        self.dimension += 1;
+        value
+    }
+
+    fn next_2d(&mut self) -> Vec2 {
+        let x = self.sample_dimension(self.dimension);
+        let y = self.sample_dimension(self.dimension + 1);
+        self.dimension += 2;
+        Vec2::new(x, y)
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
+
+/* INTERNAL FIELD */
+const PRIMES: [u32; 32] = [
+    2, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53,
+    59, 61, 67, 71, 73, 79, 83, 89, 97, 101, 103, 107, 109, 113, 127, 131
+];
+
+/// Mirror the digits of `index` in `base` around the radix point.
+fn radical_inverse(base: u32, mut index: u32) -> f64 {
+    let inv_base = 1.0 / base as f64;
+    let mut inv_base_n = 1.0;
+    let mut reversed: u64 = 0;
+
+    while index > 0 {
+        let next = index / base;
+        let digit = index - next * base;
+        reversed = reversed * base as u64 + digit as u64;
+        inv_base_n *= inv_base;
+        index = next;
+    }
+
+    reversed as f64 * inv_base_n
+}
@@ -0,0 +1,39 @@
+use super::super::super::core::{ Sampler, Vec2, math::{ Pcg32, utils::hash } };
+
+/// Purely random sampler, whose samples are independent of each other.
+/// 
+/// Each pixel owns a random stream, which is reseeded for every sample index,
+/// so samples are reproducible regardless of rendering order.
+#[derive(Clone)]
+pub struct IndependentSampler {
+    pub seed: u64,
+
+    rng: Pcg32
+}
+
+impl IndependentSampler {
+    pub fn new(seed: u64) -> IndependentSampler {
+        IndependentSampler { seed, rng: Pcg32::new(seed, 0) }
+    }
+}
+
+impl Sampler for IndependentSampler {
+    fn start_sample(&mut self, pixel: (u32, u32), index: u32) {
+        let stream = ((pixel.1 as u64) << 32) | pixel.0 as u64;
+        self.rng = Pcg32::new(hash(&[self.seed, index as u64]), stream);
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        self.rng.uniform()
+    }
+
+    fn next_2d(&mut self) -> Vec2 {
+        let x = self.rng.uniform();
+        let y = self.rng.uniform();
+        Vec2::new(x, y)
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
@@ -0,0 +1,99 @@
+use super::super::super::core::{ Sampler, Vec2, math::utils::hash };
+
+/// Owen-scrambled Sobol sampler.
+/// 
+/// Every pair of dimensions uses the first two Sobol dimensions, which form a (0,2)-sequence,
+/// with their own hash-based Owen scrambling and shuffled sample index,
+/// following Burley's *Practical Hash-based Owen Scrambling*.
+#[derive(Clone)]
+pub struct SobolSampler {
+    pub seed: u64,
+
+    // Current sample state.
+    pixel: (u32, u32),
+    index: u32,
+    dimension: u32
+}
+
+impl SobolSampler {
+    pub fn new(seed: u64) -> SobolSampler {
+        SobolSampler { seed, pixel: (0, 0), index: 0, dimension: 0 }
+    }
+
+    /// Get the scrambled 2D point for current index in the given dimension.
+    fn sample_dimension(&self, dimension: u32) -> (f64, f64) {
+        let key = hash(&[self.seed, self.pixel.0 as u64, self.pixel.1 as u64, dimension as u64]);
+        let seeds = (key as u32, (key >> 32) as u32, hash(&[key]) as u32);
+
+        let index = nested_uniform_scramble(self.index, seeds.0);
+        let x = nested_uniform_scramble(sobol(index, 0), seeds.1);
+        let y = nested_uniform_scramble(sobol(index, 1), seeds.2);
+
+        (to_unit(x), to_unit(y))
+    }
+}
+
+impl Sampler for SobolSampler {
+    fn start_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.pixel = pixel;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        let (x, _) = self.sample_dimension(self.dimension);
+        self.dimension += 1;
+        x
+    }
+
+    fn next_2d(&mut self) -> Vec2 {
+        let (x, y) = self.sample_dimension(self.dimension);
+        self.dimension += 2;
+        Vec2::new(x, y)
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
+
+/* INTERNAL FIELD */
+
+/// Map 32 bits onto `[0, 1)`.
+fn to_unit(bits: u32) -> f64 {
+    bits as f64 / 4294967296.0
+}
+
+/// Get the first (`dimension == 0`) or second (`dimension == 1`) Sobol dimension.
+fn sobol(mut index: u32, dimension: u32) -> u32 {
+    if dimension == 0 {
+        return index.reverse_bits();
+    }
+
+    let mut direction = 1u32 << 31;
+    let mut value = 0;
+    while index != 0 {
+        if index & 1 != 0 {
+            value ^= direction;
+        }
+        index >>= 1;
+        direction ^= direction >> 1;
+    }
+
+    value
+}
+
+/// Owen scrambling, aka. a random permutation of each binary digit
+/// depending on all the higher ones.
+fn nested_uniform_scramble(x: u32, seed: u32) -> u32 {
+    laine_karras_permutation(x.reverse_bits(), seed).reverse_bits()
+}
+
+fn laine_karras_permutation(mut x: u32, seed: u32) -> u32 {
+    x ^= x.wrapping_mul(0x3d20adea);
+    x = x.wrapping_add(seed);
+    x = x.wrapping_mul((seed >> 16) | 1);
+    x ^= x.wrapping_mul(0x05526c56);
+    x ^= x.wrapping_mul(0x53a22864);
+    x
+}
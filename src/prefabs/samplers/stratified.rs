@@ -0,0 +1,113 @@
+use super::super::super::core::{ Sampler, Vec2, math::utils::hash };
+
+/// Stratified (jittered) sampler.
+/// 
+/// Each dimension is divided into `spp` strata (or a grid of them for 2D),
+/// and every sample of a pixel takes a different stratum with random jitter.
+/// Strata are shuffled per pixel and dimension to decorrelate dimensions.
+#[derive(Clone)]
+pub struct StratifiedSampler {
+    /// The number of samples per pixel, which is also the number of strata.
+    pub spp: u32,
+    pub seed: u64,
+
+    // Current sample state.
+    pixel: (u32, u32),
+    index: u32,
+    dimension: u32
+}
+
+impl StratifiedSampler {
+    pub fn new(spp: u32, seed: u64) -> StratifiedSampler {
+        StratifiedSampler { spp: spp.max(1), seed, pixel: (0, 0), index: 0, dimension: 0 }
+    }
+
+    /// Get the shuffled stratum and the jitter of current sample.
+    fn stratum(&self, count: u32) -> (u32, u64) {
+        let key = hash(&[self.seed, self.pixel.0 as u64, self.pixel.1 as u64, self.dimension as u64]);
+        let stratum = permutation_element(self.index % count, count, key as u32);
+        let jitter = hash(&[key, self.index as u64]);
+
+        (stratum, jitter)
+    }
+}
+
+impl Sampler for StratifiedSampler {
+    fn start_sample(&mut self, pixel: (u32, u32), index: u32) {
+        self.pixel = pixel;
+        self.index = index;
+        self.dimension = 0;
+    }
+
+    fn next_1d(&mut self) -> f64 {
+        let (stratum, jitter) = self.stratum(self.spp);
+        self.dimension += 1;
+
+        ((stratum as f64 + to_unit(jitter)) / self.spp as f64).min(ONE_MINUS_EPSILON)
+    }
+
+    fn next_2d(&mut self) -> Vec2 {
+        // the smallest grid which contains `spp` strata.
+        let x_strata = (self.spp as f64).sqrt().ceil() as u32;
+        let y_strata = self.spp.div_ceil(x_strata);
+
+        let (stratum, jitter) = self.stratum(x_strata * y_strata);
+        self.dimension += 2;
+
+        let x = (stratum % x_strata) as f64 + to_unit(jitter);
+        let y = (stratum / x_strata) as f64 + to_unit(jitter >> 32);
+
+        Vec2::new(
+            (x / x_strata as f64).min(ONE_MINUS_EPSILON),
+            (y / y_strata as f64).min(ONE_MINUS_EPSILON)
+        )
+    }
+
+    fn clone_box(&self) -> Box<dyn Sampler> {
+        Box::new(self.clone())
+    }
+}
+
+/* INTERNAL FIELD */
+const ONE_MINUS_EPSILON: f64 = 1.0 - f64::EPSILON / 2.0;
+
+/// Map the lower 32 bits onto `[0, 1)`.
+fn to_unit(bits: u64) -> f64 {
+    (bits & 0xffffffff) as f64 / 4294967296.0
+}
+
+/// Get the `i`-th element of a random permutation of `[0, n)`,
+/// following Kensler's *Correlated Multi-Jittered Sampling*.
+fn permutation_element(mut i: u32, n: u32, seed: u32) -> u32 {
+    let mut w = n.wrapping_sub(1);
+    w |= w >> 1;
+    w |= w >> 2;
+    w |= w >> 4;
+    w |= w >> 8;
+    w |= w >> 16;
+
+    loop {
+        i ^= seed;
+        i = i.wrapping_mul(0xe170893d);
+        i ^= seed >> 16;
+        i ^= (i & w) >> 4;
+        i ^= seed >> 8;
+        i = i.wrapping_mul(0x0929eb3f);
+        i ^= seed >> 23;
+        i ^= (i & w) >> 1;
+        i = i.wrapping_mul(1 | seed >> 27);
+        i = i.wrapping_mul(0x6935fa69);
+        i ^= (i & w) >> 11;
+        i = i.wrapping_mul(0x74dcb303);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0x9e501cc3);
+        i ^= (i & w) >> 2;
+        i = i.wrapping_mul(0xc860a3df);
+        i &= w;
+        i ^= i >> 5;
+
+        if i < n { break; }
+    }
+
+    (i.wrapping_add(seed)) % n
+}
@@ -1,2 +1,93 @@
 pub mod sphere;
-pub use sphere::Sphere;
\ No newline at end of file
+pub use sphere::Sphere;
+
+pub mod triangle;
+pub use triangle::Triangle;
+
+pub mod quad;
+pub use quad::Quad;
+
+#[cfg(test)]
+mod shapes_tests {
+    use super::*;
+    use crate::{ core::{ Hittable, Ray }, math::{ Interval, Vec2, Vec3 } };
+
+    fn assert_near(a: Vec3, b: Vec3) {
+        assert!((a - b).length() < 1e-9, "{:?} != {:?}", a, b);
+    }
+
+    #[test]
+    fn sphere_tangents() {
+        let sphere = Sphere::new(Vec3::new(0.0, 0.0, 0.0), 1.0);
+        let limit = Interval::new(0.001, f64::INFINITY);
+
+        let hit = sphere.hit(&Ray::new(Vec3::new(3.0, 0.0, 0.0), Vec3::new(-1.0, 0.0, 0.0)), limit).unwrap();
+        assert_near(hit.tangent, Vec3::new(0.0, 0.0, -1.0));
+        assert_near(hit.bitangent, Vec3::new(0.0, 1.0, 0.0));
+
+        // the frame stays orthonormal at the poles.
+        let hit = sphere.hit(&Ray::new(Vec3::new(0.0, 3.0, 0.0), Vec3::new(0.0, -1.0, 0.0)), limit).unwrap();
+        assert!(hit.tangent.dot(hit.normal).abs() < 1e-9);
+        assert!(hit.bitangent.dot(hit.tangent).abs() < 1e-9);
+    }
+
+    #[test]
+    fn triangle_hit() {
+        let triangle = Triangle::with_uvs(
+            [Vec3::new(0.0, 0.0, 0.0), Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 2.0, 0.0)],
+            [Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0)]
+        );
+        let limit = Interval::new(0.001, f64::INFINITY);
+
+        let hit = triangle.hit(&Ray::new(Vec3::new(0.5, 1.0, -1.0), Vec3::new(0.0, 0.0, 1.0)), limit).unwrap();
+        assert_eq!(hit.step, 1.0);
+        assert!(!hit.is_front);
+        assert_near(hit.normal, Vec3::new(0.0, 0.0, -1.0));
+        assert!((hit.uv.x - 0.25).abs() < 1e-9 && (hit.uv.y - 0.5).abs() < 1e-9);
+        assert_near(hit.tangent, Vec3::new(1.0, 0.0, 0.0));
+        assert_near(hit.bitangent, Vec3::new(0.0, 1.0, 0.0));
+
+        assert!(triangle.hit(&Ray::new(Vec3::new(1.5, 1.5, -1.0), Vec3::new(0.0, 0.0, 1.0)), limit).is_none());
+    }
+
+    #[test]
+    fn triangle_normals() {
+        let vertices = [Vec3::new(0.0, 0.0, 0.0), Vec3::new(1.0, 0.0, 0.0), Vec3::new(0.0, 1.0, 0.0)];
+        let normals = [Vec3::new(-1.0, 0.0, 1.0), Vec3::new(1.0, 0.0, 1.0), Vec3::new(-1.0, 0.0, 1.0)];
+        let limit = Interval::new(0.001, f64::INFINITY);
+
+        // the shading normal is interpolated, while the geometric one stays flat.
+        let triangle = Triangle::new(vertices).with_normals(normals);
+        let ray = Ray::new(Vec3::new(0.5, 0.25, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = triangle.hit(&ray, limit).unwrap();
+        assert!(hit.is_front);
+        assert_near(hit.geometric_normal, Vec3::new(0.0, 0.0, 1.0));
+        assert_near(hit.normal, Vec3::new(0.0, 0.0, 1.0));
+
+        let ray = Ray::new(Vec3::new(0.75, 0.1, 1.0), Vec3::new(0.0, 0.0, -1.0));
+        let hit = triangle.hit(&ray, limit).unwrap();
+        assert!(hit.normal.x > 0.0 && hit.normal.z > 0.0);
+        assert_near(hit.geometric_normal, Vec3::new(0.0, 0.0, 1.0));
+
+        // the front face follows vertex normals, regardless of winding.
+        let flipped = Triangle::new(vertices).with_normals(normals.map(|n| -n));
+        let hit = flipped.hit(&ray, limit).unwrap();
+        assert!(!hit.is_front);
+        assert_near(hit.geometric_normal, Vec3::new(0.0, 0.0, 1.0));
+        assert!(hit.normal.dot(hit.geometric_normal) > 0.0);
+    }
+
+    #[test]
+    fn quad_hit() {
+        let quad = Quad::new(Vec3::new(-1.0, -1.0, 0.0), Vec3::new(2.0, 0.0, 0.0), Vec3::new(0.0, 4.0, 0.0));
+        let limit = Interval::new(0.001, f64::INFINITY);
+
+        let hit = quad.hit(&Ray::new(Vec3::new(0.5, 0.0, 2.0), Vec3::new(0.0, 0.0, -1.0)), limit).unwrap();
+        assert!(hit.is_front);
+        assert_eq!(hit.step, 2.0);
+        assert!((hit.uv.x - 0.75).abs() < 1e-9 && (hit.uv.y - 0.25).abs() < 1e-9);
+        assert_near(hit.bitangent, Vec3::new(0.0, 1.0, 0.0));
+
+        assert!(quad.hit(&Ray::new(Vec3::new(1.5, 0.0, 2.0), Vec3::new(0.0, 0.0, -1.0)), limit).is_none());
+    }
+}
@@ -0,0 +1,69 @@
+use super::super::super::core::{
+    Hittable, HittingInfo, Ray, Vec2, Vec3, Interval
+};
+
+/// Parallelogram spanned by two edges from a corner, like cards of foliage and decals.
+///
+/// `uv` goes from `(0, 0)` at `corner` to `(1, 1)` at `corner + u + v`,
+/// and the front face is where `u.cross(v)` points.
+pub struct Quad {
+    pub corner: Vec3,
+    pub u: Vec3,
+    pub v: Vec3
+}
+
+impl Quad {
+    pub fn new(corner: Vec3, u: Vec3, v: Vec3) -> Quad {
+        Quad { corner, u, v }
+    }
+}
+
+impl Hittable for Quad {
+    fn hit(&self, ray: &Ray, step_limit: Interval) -> Option<HittingInfo> {
+        let n = self.u.cross(self.v);
+        let denom = n.dot(ray.dir);
+        if denom.abs() < 1e-12 {
+            return None;
+        }
+
+        let step = n.dot(self.corner - ray.ori) / denom;
+        if !step_limit.surrounds(step) {
+            return None;
+        }
+
+        // solve planar coordinates of the hitted position along both edges.
+        let point = ray.position(step);
+        let w = n / n.dot(n);
+        let p = point - self.corner;
+        let a = w.dot(p.cross(self.v));
+        let b = w.dot(self.u.cross(p));
+        if !(0.0..=1.0).contains(&a) || !(0.0..=1.0).contains(&b) {
+            return None;
+        }
+
+        let tangent = self.u.normalized();
+        let mut normal = n.normalized();
+        let mut bitangent = normal.cross(tangent);
+        if bitangent.dot(self.v) < 0.0 {
+            bitangent = -bitangent;
+        }
+
+        let mut is_front = true;
+        if denom > 0.0 {
+            is_front = false;
+            normal = -normal;
+        }
+
+        Some(HittingInfo {
+            position: point,
+            normal, geometric_normal: normal,
+            step, is_front,
+            uv: Vec2::new(a, b),
+            tangent, bitangent
+        })
+    }
+
+    fn kind(&self) -> &'static str {
+        "quad"
+    }
+}
@@ -1,5 +1,7 @@
+use std::f64::consts::PI;
 use super::super::super::core::{
-    Hittable, HittingInfo, Ray, Vec3, Interval
+    Hittable, HittingInfo, Ray, Vec2, Vec3, Interval,
+    math::utils::orthonormal_basis
 };
 
 pub struct Sphere {
@@ -11,6 +13,28 @@ impl Sphere {
     pub fn new(center: Vec3, radius: f64) -> Self {
         Sphere { center, radius }
     }
+
+    /// Get the spherical coordinate of a point on the unit sphere.
+    /// 
+    /// `u` goes around the y-axis from `-x`, and `v` goes from the bottom (`-y`) to the top.
+    pub fn uv(point: Vec3) -> Vec2 {
+        let phi = (-point.z).atan2(point.x) + PI;
+        let theta = (-point.y).clamp(-1.0, 1.0).acos();
+
+        Vec2::new(phi / (2.0 * PI), theta / PI)
+    }
+
+    /// Get the directions where `u` and `v` increase at a point on the unit sphere.
+    fn tangents(point: Vec3) -> (Vec3, Vec3) {
+        let tangent = Vec3::new(point.z, 0.0, -point.x);
+        if tangent.length_square() < 1e-12 {
+            // `u` is singular at the poles.
+            return orthonormal_basis(point);
+        }
+
+        let tangent = tangent.normalized();
+        (tangent, point.cross(tangent))
+    }
 }
 
 impl Hittable for Sphere {
@@ -37,6 +61,8 @@ impl Hittable for Sphere {
 
         let mut is_front = true;
         let mut normal = (point - self.center).normalized();
+        let uv = Self::uv(normal);
+        let (tangent, bitangent) = Self::tangents(normal);
         if normal.dot(ray.dir) > 0.0 {
             is_front = false;
             normal = -normal;
@@ -44,7 +70,13 @@ impl Hittable for Sphere {
 
         Some(HittingInfo {
             position: point, 
-            normal, step, is_front
+            normal, geometric_normal: normal,
+            step, is_front, uv,
+            tangent, bitangent
         })
     }
+
+    fn kind(&self) -> &'static str {
+        "sphere"
+    }
 }
\ No newline at end of file
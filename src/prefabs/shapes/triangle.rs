@@ -0,0 +1,130 @@
+use super::super::super::core::{
+    Hittable, HittingInfo, Ray, Vec2, Vec3, Interval,
+    math::utils::orthonormal_basis
+};
+
+/// Single triangle, whose front face is where the vertices go counter-clockwise.
+///
+/// If vertex normals are given, they are interpolated as the shading normal,
+/// and the front face turns to agree with them.
+pub struct Triangle {
+    pub vertices: [Vec3; 3],
+    /// The surface coordinates of vertices, which are interpolated across the triangle.
+    pub uvs: [Vec2; 3],
+    /// The normals(normalized) of vertices for smooth shading.
+    pub normals: Option<[Vec3; 3]>
+}
+
+impl Triangle {
+    /// Create a triangle, whose vertices are mapped to `(0, 0)`, `(1, 0)` and `(0, 1)`.
+    pub fn new(vertices: [Vec3; 3]) -> Triangle {
+        Triangle {
+            vertices,
+            uvs: [Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), Vec2::new(0.0, 1.0)],
+            normals: None
+        }
+    }
+
+    pub fn with_uvs(vertices: [Vec3; 3], uvs: [Vec2; 3]) -> Triangle {
+        Triangle { vertices, uvs, normals: None }
+    }
+
+    /// Set normals of vertices, which are normalized.
+    pub fn with_normals(mut self, normals: [Vec3; 3]) -> Triangle {
+        self.normals = Some(normals.map(|n| n.normalized()));
+        self
+    }
+
+    /// Get the directions where `u` and `v` increase, with respect to the front normal.
+    fn tangents(&self, normal: Vec3) -> (Vec3, Vec3) {
+        let e1 = self.vertices[1] - self.vertices[0];
+        let e2 = self.vertices[2] - self.vertices[0];
+        let duv1 = self.uvs[1] - self.uvs[0];
+        let duv2 = self.uvs[2] - self.uvs[0];
+
+        let det = duv1.x * duv2.y - duv1.y * duv2.x;
+        if det.abs() < 1e-12 {
+            // uvs are degenerate, so any frame will do.
+            return orthonormal_basis(normal);
+        }
+
+        let dpdu = (e1 * duv2.y - e2 * duv1.y) / det;
+        let dpdv = (e2 * duv1.x - e1 * duv2.x) / det;
+
+        let tangent = dpdu - normal * normal.dot(dpdu);
+        if tangent.length_square() < 1e-24 {
+            return orthonormal_basis(normal);
+        }
+        let tangent = tangent.normalized();
+
+        // uvs may be mirrored, which flips the bitangent.
+        let bitangent = normal.cross(tangent);
+        if bitangent.dot(dpdv) < 0.0 { (tangent, -bitangent) } else { (tangent, bitangent) }
+    }
+}
+
+impl Hittable for Triangle {
+    fn hit(&self, ray: &Ray, step_limit: Interval) -> Option<HittingInfo> {
+        // *Möller–Trumbore* intersection.
+        let e1 = self.vertices[1] - self.vertices[0];
+        let e2 = self.vertices[2] - self.vertices[0];
+
+        let pvec = ray.dir.cross(e2);
+        let det = e1.dot(pvec);
+        if det.abs() < 1e-12 {
+            return None;
+        }
+        let inv_det = 1.0 / det;
+
+        let tvec = ray.ori - self.vertices[0];
+        let b1 = tvec.dot(pvec) * inv_det;
+        if !(0.0..=1.0).contains(&b1) {
+            return None;
+        }
+
+        let qvec = tvec.cross(e1);
+        let b2 = ray.dir.dot(qvec) * inv_det;
+        if b2 < 0.0 || b1 + b2 > 1.0 {
+            return None;
+        }
+
+        let step = e2.dot(qvec) * inv_det;
+        if !step_limit.surrounds(step) {
+            return None;
+        }
+
+        let b0 = 1.0 - b1 - b2;
+        let uv = self.uvs[0] * b0 + self.uvs[1] * b1 + self.uvs[2] * b2;
+
+        let mut geometric_normal = e1.cross(e2).normalized();
+        let mut normal = match self.normals {
+            Some(n) => {
+                let normal = (n[0] * b0 + n[1] * b1 + n[2] * b2).normalized();
+                if normal.dot(geometric_normal) < 0.0 {
+                    geometric_normal = -geometric_normal;
+                }
+                normal
+            },
+            None => geometric_normal
+        };
+        let (tangent, bitangent) = self.tangents(normal);
+
+        let mut is_front = true;
+        if geometric_normal.dot(ray.dir) > 0.0 {
+            is_front = false;
+            geometric_normal = -geometric_normal;
+            normal = -normal;
+        }
+
+        Some(HittingInfo {
+            position: ray.position(step),
+            normal, geometric_normal,
+            step, is_front, uv,
+            tangent, bitangent
+        })
+    }
+
+    fn kind(&self) -> &'static str {
+        "triangle"
+    }
+}
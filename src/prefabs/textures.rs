@@ -0,0 +1,81 @@
+pub mod image_texture;
+pub use image_texture::{ ImageTexture, WrapMode };
+
+pub mod checker;
+pub use checker::Checker;
+
+pub mod perlin;
+pub use perlin::{ Perlin, NoiseKind, NoiseTexture };
+
+pub mod marble;
+pub use marble::Marble;
+
+pub mod wood;
+pub use wood::Wood;
+
+#[cfg(test)]
+mod textures_tests {
+    use super::*;
+    use crate::{ core::Texture, math::{ Vec2, Vec3 } };
+
+    fn checker(wrap: WrapMode) -> ImageTexture {
+        // top row: black, white; bottom row: white, black.
+        let (b, w) = (Vec3::from_scalar(0.0), Vec3::from_scalar(1.0));
+        ImageTexture::new((2, 2), vec![b, w, w, b], wrap)
+    }
+
+    #[test]
+    fn texel_centers() {
+        let texture = checker(WrapMode::Clamp);
+        let origin = Vec3::from_scalar(0.0);
+
+        assert_eq!(texture.evaluate(Vec2::new(0.25, 0.75), origin), Vec3::from_scalar(0.0));
+        assert_eq!(texture.evaluate(Vec2::new(0.75, 0.75), origin), Vec3::from_scalar(1.0));
+        assert_eq!(texture.evaluate(Vec2::new(0.25, 0.25), origin), Vec3::from_scalar(1.0));
+        assert_eq!(texture.evaluate(Vec2::new(0.5, 0.5), origin), Vec3::from_scalar(0.5));
+    }
+
+    #[test]
+    fn wrap_modes() {
+        let origin = Vec3::from_scalar(0.0);
+        let u = |wrap, u| checker(wrap).evaluate(Vec2::new(u, 0.75), origin).x;
+
+        // the edge of image blends with the opposite texel only when repeating.
+        assert_eq!(u(WrapMode::Repeat, 0.0), 0.5);
+        assert_eq!(u(WrapMode::Clamp, 0.0), 0.0);
+        assert_eq!(u(WrapMode::Mirror, 0.0), 0.0);
+
+        assert_eq!(u(WrapMode::Repeat, 1.25), 0.0);
+        assert_eq!(u(WrapMode::Mirror, 1.25), 1.0);
+        assert_eq!(u(WrapMode::Clamp, 1.25), 1.0);
+    }
+
+    #[test]
+    fn checker_parity() {
+        let (b, w) = (Vec3::from_scalar(0.0), Vec3::from_scalar(1.0));
+        let flat = Checker::new(b, w, 4.0, false);
+        let solid = Checker::new(b, w, 1.0, true);
+
+        assert_eq!(flat.evaluate(Vec2::new(0.1, 0.1), w), b);
+        assert_eq!(flat.evaluate(Vec2::new(0.3, 0.1), w), w);
+        assert_eq!(solid.evaluate(Vec2::new(0.0, 0.0), Vec3::new(0.5, 0.5, 0.5)), b);
+        assert_eq!(solid.evaluate(Vec2::new(0.0, 0.0), Vec3::new(-0.5, 0.5, 0.5)), w);
+    }
+
+    #[test]
+    fn perlin_noise() {
+        let perlin = Perlin::new(7);
+
+        // noise vanishes at lattice points, and is reproducible from seed.
+        assert_eq!(perlin.noise(Vec3::new(3.0, -2.0, 5.0)), 0.0);
+        let p = Vec3::new(0.3, 1.7, -2.4);
+        assert_eq!(perlin.noise(p), Perlin::new(7).noise(p));
+
+        for i in 0..1000 {
+            let p = Vec3::new(i as f64 * 0.173, i as f64 * 0.071, i as f64 * -0.029);
+            assert!(perlin.noise(p).abs() <= 1.0);
+            assert!((-1.0..=1.0).contains(&perlin.fbm(p, 5)));
+            assert!((0.0..=1.0).contains(&perlin.turbulence(p, 5)));
+        }
+    }
+}
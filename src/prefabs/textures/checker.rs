@@ -0,0 +1,32 @@
+use super::super::super::core::{ Texture, TexParam, Vec2, Vec3 };
+
+/// Checkerboard alternating between two parameters.
+///
+/// A *solid* checker is a 3D lattice of cubes in world space, otherwise squares are laid out in uv space.
+pub struct Checker {
+    pub even: TexParam,
+    pub odd: TexParam,
+    /// The number of cells per unit length (or per unit uv).
+    pub scale: f64,
+    pub solid: bool
+}
+
+impl Checker {
+    pub fn new(even: impl Into<TexParam>, odd: impl Into<TexParam>, scale: f64, solid: bool) -> Checker {
+        Checker { even: even.into(), odd: odd.into(), scale, solid }
+    }
+}
+
+impl Texture for Checker {
+    fn evaluate(&self, uv: Vec2, position: Vec3) -> Vec3 {
+        let cell = if self.solid {
+            let p = position * self.scale;
+            p.x.floor() as i64 + p.y.floor() as i64 + p.z.floor() as i64
+        } else {
+            (uv.x * self.scale).floor() as i64 + (uv.y * self.scale).floor() as i64
+        };
+
+        if cell.rem_euclid(2) == 0 { self.even.evaluate(uv, position) }
+        else { self.odd.evaluate(uv, position) }
+    }
+}
@@ -0,0 +1,102 @@
+use super::super::super::{
+    core::{ Texture, Vec2, Vec3 },
+    utils::img_saver::TransferFunction
+};
+
+/// How texture coordinates outside `[0, 1]` are mapped.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WrapMode {
+    /// Tile the image.
+    Repeat,
+    /// Tile the image, flipping every other copy.
+    Mirror,
+    /// Extend the edge texels.
+    Clamp
+}
+
+/// Texture sampling an image with bilinear filtering.
+///
+/// `uv = (0, 0)` is the bottom-left corner of image, and `uv = (1, 1)` is the top-right one.
+pub struct ImageTexture {
+    /// The width and height of image.
+    pub size: (u32, u32),
+    /// Linear texels in row-major order, from the top row.
+    pub texels: Vec<Vec3>,
+    pub wrap: WrapMode
+}
+
+impl ImageTexture {
+    pub fn new(size: (u32, u32), texels: Vec<Vec3>, wrap: WrapMode) -> ImageTexture {
+        if texels.len() != (size.0 * size.1) as usize || texels.is_empty() {
+            panic!("image texture of {:?} requires {} texels, but got {}!", size, size.0 * size.1, texels.len());
+        }
+        ImageTexture { size, texels, wrap }
+    }
+
+    /// Load texture from image file.
+    ///
+    /// - `srgb` whether the image stores sRGB-encoded color, which is decoded into linear.
+    ///   Non-color data (e.g. masks and normal maps) should be loaded with `false`.
+    pub fn load(path: &str, wrap: WrapMode, srgb: bool) -> Result<ImageTexture, String> {
+        let image = image::open(path)
+                        .map_err(|err| format!("failed to load texture \"{}\": {}", path, err))?
+                        .into_rgb32f();
+
+        let decode = |c: f32| -> f64 {
+            if srgb { TransferFunction::Srgb.decode(c as f64) } else { c as f64 }
+        };
+        let texels = image.pixels()
+                          .map(|p| Vec3::new(decode(p.0[0]), decode(p.0[1]), decode(p.0[2])))
+                          .collect();
+
+        Ok(ImageTexture::new(image.dimensions(), texels, wrap))
+    }
+
+    /// Load the alpha channel of image file as a texture, like opacity masks.
+    ///
+    /// Images without alpha are fully opaque.
+    pub fn load_alpha(path: &str, wrap: WrapMode) -> Result<ImageTexture, String> {
+        let image = image::open(path)
+                        .map_err(|err| format!("failed to load texture \"{}\": {}", path, err))?
+                        .into_rgba32f();
+
+        let texels = image.pixels()
+                          .map(|p| Vec3::from_scalar(p.0[3] as f64))
+                          .collect();
+
+        Ok(ImageTexture::new(image.dimensions(), texels, wrap))
+    }
+
+    /// Get texel at integer coordinate, which is wrapped into image.
+    fn texel(&self, x: i64, y: i64) -> Vec3 {
+        let x = Self::wrap_index(x, self.size.0 as i64, self.wrap);
+        let y = Self::wrap_index(y, self.size.1 as i64, self.wrap);
+        self.texels[(y * self.size.0 as i64 + x) as usize]
+    }
+
+    fn wrap_index(i: i64, n: i64, wrap: WrapMode) -> i64 {
+        match wrap {
+            WrapMode::Repeat => i.rem_euclid(n),
+            WrapMode::Clamp => i.clamp(0, n - 1),
+            WrapMode::Mirror => {
+                let i = i.rem_euclid(2 * n);
+                if i < n { i } else { 2 * n - 1 - i }
+            }
+        }
+    }
+}
+
+impl Texture for ImageTexture {
+    fn evaluate(&self, uv: Vec2, _position: Vec3) -> Vec3 {
+        // texel (x, y) centers at ((x + 0.5) / width, 1 - (y + 0.5) / height).
+        let x = uv.x * self.size.0 as f64 - 0.5;
+        let y = (1.0 - uv.y) * self.size.1 as f64 - 0.5;
+        let (x0, y0) = (x.floor(), y.floor());
+        let (tx, ty) = (x - x0, y - y0);
+        let (x0, y0) = (x0 as i64, y0 as i64);
+
+        let top = (1.0 - tx) * self.texel(x0, y0) + tx * self.texel(x0 + 1, y0);
+        let bottom = (1.0 - tx) * self.texel(x0, y0 + 1) + tx * self.texel(x0 + 1, y0 + 1);
+        (1.0 - ty) * top + ty * bottom
+    }
+}
@@ -0,0 +1,33 @@
+use super::super::super::core::{ Texture, TexParam, Vec2, Vec3 };
+use super::Perlin;
+
+/// Marble veins, which are sine bands along the z-axis distorted by turbulence.
+pub struct Marble {
+    pub perlin: Perlin,
+    /// The frequency of bands in world space.
+    pub scale: f64,
+    /// How strongly turbulence bends the bands.
+    pub distortion: f64,
+    pub octaves: u32,
+    pub base: TexParam,
+    pub vein: TexParam
+}
+
+impl Marble {
+    pub fn new(
+        seed: u64, scale: f64, distortion: f64, octaves: u32,
+        base: impl Into<TexParam>, vein: impl Into<TexParam>
+    ) -> Marble {
+        Marble { perlin: Perlin::new(seed), scale, distortion, octaves, base: base.into(), vein: vein.into() }
+    }
+}
+
+impl Texture for Marble {
+    fn evaluate(&self, uv: Vec2, position: Vec3) -> Vec3 {
+        let p = position * self.scale;
+        let phase = p.z + self.distortion * self.perlin.turbulence(p, self.octaves);
+        let t = 0.5 * (1.0 + phase.sin());
+
+        (1.0 - t) * self.base.evaluate(uv, position) + t * self.vein.evaluate(uv, position)
+    }
+}
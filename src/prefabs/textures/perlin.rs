@@ -0,0 +1,122 @@
+use super::super::super::core::{ Texture, TexParam, Vec2, Vec3, math::Pcg32 };
+
+/// *Perlin* gradient noise, whose lattice is shuffled by a seed.
+#[derive(Clone)]
+pub struct Perlin {
+    perm: Vec<u8>
+}
+
+impl Perlin {
+    /// Gradients of improved noise, pointing to the edges of a cube.
+    const GRADIENTS: [(f64, f64, f64); 16] = [
+        (1.0, 1.0, 0.0), (-1.0, 1.0, 0.0), (1.0, -1.0, 0.0), (-1.0, -1.0, 0.0),
+        (1.0, 0.0, 1.0), (-1.0, 0.0, 1.0), (1.0, 0.0, -1.0), (-1.0, 0.0, -1.0),
+        (0.0, 1.0, 1.0), (0.0, -1.0, 1.0), (0.0, 1.0, -1.0), (0.0, -1.0, -1.0),
+        (1.0, 1.0, 0.0), (0.0, -1.0, 1.0), (-1.0, 1.0, 0.0), (0.0, -1.0, -1.0)
+    ];
+
+    pub fn new(seed: u64) -> Perlin {
+        let mut perm: Vec<u8> = (0..=255).collect();
+        let mut rng = Pcg32::new(seed, 0);
+        for i in (1..perm.len()).rev() {
+            let j = (rng.uniform() * (i + 1) as f64) as usize;
+            perm.swap(i, j);
+        }
+        Perlin { perm }
+    }
+
+    /// Get noise at a point, in about `[-1, 1]`, which is 0 at lattice points.
+    pub fn noise(&self, p: Vec3) -> f64 {
+        let (fx, fy, fz) = (p.x.floor(), p.y.floor(), p.z.floor());
+        let (x, y, z) = (p.x - fx, p.y - fy, p.z - fz);
+        let (ix, iy, iz) = (fx as i64, fy as i64, fz as i64);
+
+        let fade = |t: f64| t * t * t * (t * (t * 6.0 - 15.0) + 10.0);
+        let lerp = |t: f64, a: f64, b: f64| a + t * (b - a);
+        let grad = |dx: i64, dy: i64, dz: i64| -> f64 {
+            let h = self.hash(ix + dx, iy + dy, iz + dz);
+            let (gx, gy, gz) = Self::GRADIENTS[h & 15];
+            gx * (x - dx as f64) + gy * (y - dy as f64) + gz * (z - dz as f64)
+        };
+
+        let (u, v, w) = (fade(x), fade(y), fade(z));
+        lerp(w,
+            lerp(v, lerp(u, grad(0, 0, 0), grad(1, 0, 0)), lerp(u, grad(0, 1, 0), grad(1, 1, 0))),
+            lerp(v, lerp(u, grad(0, 0, 1), grad(1, 0, 1)), lerp(u, grad(0, 1, 1), grad(1, 1, 1)))
+        )
+    }
+
+    /// Fractional Brownian motion, which sums `octaves` layers of noise
+    /// with doubling frequency and halving amplitude.
+    pub fn fbm(&self, p: Vec3, octaves: u32) -> f64 {
+        self.octaves(p, octaves, |n| n)
+    }
+
+    /// Like [`Perlin::fbm`], but sums the absolute value of noise, in `[0, 1]`.
+    pub fn turbulence(&self, p: Vec3, octaves: u32) -> f64 {
+        self.octaves(p, octaves, f64::abs)
+    }
+
+    fn octaves(&self, p: Vec3, octaves: u32, layer: impl Fn(f64) -> f64) -> f64 {
+        let (mut sum, mut norm) = (0.0, 0.0);
+        let (mut frequency, mut amplitude) = (1.0, 1.0);
+        for _ in 0..octaves.max(1) {
+            sum += amplitude * layer(self.noise(p * frequency));
+            norm += amplitude;
+            frequency *= 2.0;
+            amplitude *= 0.5;
+        }
+        sum / norm
+    }
+
+    fn hash(&self, x: i64, y: i64, z: i64) -> usize {
+        let p = |i: i64| self.perm[(i & 255) as usize] as i64;
+        p(p(p(x) + y) + z) as usize
+    }
+}
+
+/// How [`NoiseTexture`] maps noise.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum NoiseKind {
+    /// A single layer of noise.
+    Perlin,
+    /// Fractional Brownian motion.
+    Fbm,
+    /// Sum of absolute noise.
+    Turbulence
+}
+
+/// Solid texture blending two parameters by noise.
+pub struct NoiseTexture {
+    pub perlin: Perlin,
+    pub kind: NoiseKind,
+    /// The frequency of noise in world space.
+    pub scale: f64,
+    pub octaves: u32,
+    /// Value where noise is 0.
+    pub low: TexParam,
+    /// Value where noise is 1.
+    pub high: TexParam
+}
+
+impl NoiseTexture {
+    pub fn new(
+        seed: u64, kind: NoiseKind, scale: f64, octaves: u32,
+        low: impl Into<TexParam>, high: impl Into<TexParam>
+    ) -> NoiseTexture {
+        NoiseTexture { perlin: Perlin::new(seed), kind, scale, octaves, low: low.into(), high: high.into() }
+    }
+}
+
+impl Texture for NoiseTexture {
+    fn evaluate(&self, uv: Vec2, position: Vec3) -> Vec3 {
+        let p = position * self.scale;
+        let t = match self.kind {
+            NoiseKind::Perlin => 0.5 * (1.0 + self.perlin.noise(p)),
+            NoiseKind::Fbm => 0.5 * (1.0 + self.perlin.fbm(p, self.octaves)),
+            NoiseKind::Turbulence => self.perlin.turbulence(p, self.octaves)
+        }.clamp(0.0, 1.0);
+
+        (1.0 - t) * self.low.evaluate(uv, position) + t * self.high.evaluate(uv, position)
+    }
+}
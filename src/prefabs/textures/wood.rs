@@ -0,0 +1,33 @@
+use super::super::super::core::{ Texture, TexParam, Vec2, Vec3 };
+use super::Perlin;
+
+/// Wood growth rings, which are concentric around the y-axis and distorted by noise.
+pub struct Wood {
+    pub perlin: Perlin,
+    /// The number of rings per unit length.
+    pub rings: f64,
+    /// How strongly noise shifts the rings, in rings.
+    pub distortion: f64,
+    pub light: TexParam,
+    pub dark: TexParam
+}
+
+impl Wood {
+    pub fn new(
+        seed: u64, rings: f64, distortion: f64,
+        light: impl Into<TexParam>, dark: impl Into<TexParam>
+    ) -> Wood {
+        Wood { perlin: Perlin::new(seed), rings, distortion, light: light.into(), dark: dark.into() }
+    }
+}
+
+impl Texture for Wood {
+    fn evaluate(&self, uv: Vec2, position: Vec3) -> Vec3 {
+        let radius = (position.x * position.x + position.z * position.z).sqrt() * self.rings;
+        let ring = radius + self.distortion * self.perlin.noise(position * 4.0);
+        // sharpen the late wood at the end of every ring.
+        let t = ring.rem_euclid(1.0).powi(3);
+
+        (1.0 - t) * self.light.evaluate(uv, position) + t * self.dark.evaluate(uv, position)
+    }
+}
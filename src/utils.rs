@@ -1,2 +1,3 @@
 pub mod cfg_loader;
-pub mod img_saver;
\ No newline at end of file
+pub mod img_saver;
+pub mod checkpoint;
\ No newline at end of file
@@ -1,14 +1,15 @@
 //! Parse and reflect config-file(.cfg) into objects.
 use crate::{
     prefabs,
-    BackendConfig,
-    Material, Hittable,
+    BackendConfig, AdaptiveConfig, ProgressiveConfig, TileConfig, TileOrder, Tile,
+    Material, Hittable, Filter, Sampler, Texture, TexParam,
+    utils::img_saver::{ ToneMapping, TransferFunction },
     Scene, Entity,
     math::{ Vec3, Vec2 }
 };
 
-use std::{ cell::RefCell, fs, rc::Rc };
-use jzon::{ self, object::Object, Array };
+use std::{ cell::RefCell, fs, path::Path, rc::Rc, time::Duration };
+use jzon::{ self, object::Object, Array, JsonValue };
 use image::ColorType;
 use lazy_regex::regex;
 
@@ -29,18 +30,43 @@ pub struct ConfigRes {
     pub target_name: String,
     pub target_pixel: ColorType,
     pub target_resolution: (u32, u32),
+    pub target_tonemap: ToneMapping,
+    pub target_exposure: f64,
+    pub target_transfer: TransferFunction,
+    pub target_transparent_bg: bool,
+    pub target_spp_map: Option<String>,
+    pub target_checkpoint: Option<String>,
+    pub target_checkpoint_interval: Duration,
+    pub target_crop: Option<Tile>,
+    pub target_crop_full: bool,
 
     pub renderer_backend: BackendConfig,
     pub renderer_bvh_acc: bool,
     pub renderer_max_depth: u32,
     pub renderer_spp: u32,
+    pub renderer_adaptive: Option<AdaptiveConfig>,
+    pub renderer_progressive: Option<ProgressiveConfig>,
+    pub renderer_tile: TileConfig,
+    pub renderer_time_limit: Option<Duration>,
+    pub renderer_filter_name: String,
+    pub renderer_filter: Rc<dyn Filter>,
+    pub renderer_sampler_name: String,
+    pub renderer_sampler: Box<dyn Sampler>,
+    pub renderer_seed: u64,
 
     pub camera_pos: Vec3,
     pub camera_dir: Vec3,
     pub camera_fov: f64,
     pub camera_viewport: Vec2,
 
-    pub scene: Option<Scene>
+    pub scene: Option<Scene>,
+    /// Hash of the settings affecting sample accumulation,
+    /// which tells whether a checkpoint can be resumed.
+    /// 
+    /// Sample counts (`spp`, `adaptive` and `progressive`) and post-processing are excluded,
+    /// so a resumed rendering may take more samples or be saved differently.
+    /// Contents of image textures are included.
+    pub scene_hash: u64
 }
 
 /// Default configurations
@@ -52,18 +78,38 @@ impl Default for ConfigRes {
             target_name: "out.png".to_string(),
             target_pixel: ColorType::Rgb8,
             target_resolution: (128, 128),
+            target_tonemap: ToneMapping::Clamp,
+            target_exposure: 0.0,
+            // `@transfer: gamma(2)` reproduces renders of earlier versions, which encoded with `sqrt`.
+            target_transfer: TransferFunction::Srgb,
+            target_transparent_bg: false,
+            target_spp_map: None,
+            target_checkpoint: None,
+            target_checkpoint_interval: Duration::from_secs(60),
+            target_crop: None,
+            target_crop_full: false,
 
             renderer_backend: BackendConfig::CPUDrivenS,
             renderer_bvh_acc: false,
             renderer_max_depth: 8,
             renderer_spp: 8,
+            renderer_adaptive: None,
+            renderer_progressive: None,
+            renderer_tile: TileConfig::default(),
+            renderer_time_limit: None,
+            renderer_filter_name: "box".to_string(),
+            renderer_filter: Rc::new(prefabs::filters::BoxFilter::new(0.5)),
+            renderer_sampler_name: "independent".to_string(),
+            renderer_sampler: Box::new(prefabs::samplers::IndependentSampler::new(0)),
+            renderer_seed: 0,
 
             camera_pos: Vec3::from_scalar(0.0),
             camera_dir: Vec3::new(0.0, 0.0, 1.0),
             camera_fov: 45.0,
             camera_viewport: Vec2::from_scalar(0.5),
 
-            scene: None
+            scene: None,
+            scene_hash: 0
         }
     }
 }
@@ -86,7 +132,8 @@ pub fn from_file(path: &str) -> ConfigRes {
     let mut block_name = Block::None;
     let mut scene_string = String::new();
     let bmx_head_re = regex!(r"\[\s*(\w+)\s*]");
-    let bmx_pair_re = regex!(r"@(\w+):\s*([\w\s\(\).,]+)");
+    let bmx_pair_re = regex!(r"@(\w+):\s*([\w\s\(\)\{\}:.,-]+)");
+    let mut filter_radius = None;
 
     for line in cfg_content.lines()
                         .map(|x| { *line_counter.borrow_mut() += 1; x.trim() })
@@ -123,8 +170,16 @@ pub fn from_file(path: &str) -> ConfigRes {
                         "pixel" => {
                             let color_t;
                             match value {
+                                "L8"    | "l8"    => color_t = ColorType::L8,
+                                "L16"   | "l16"   => color_t = ColorType::L16,
+                                "LA8"   | "la8"   => color_t = ColorType::La8,
+                                "LA16"  | "la16"  => color_t = ColorType::La16,
                                 "RGB8"  | "rgb8"  => color_t = ColorType::Rgb8,
                                 "RGB16" | "rgb16" => color_t = ColorType::Rgb16,
+                                "RGBA8"  | "rgba8"  => color_t = ColorType::Rgba8,
+                                "RGBA16" | "rgba16" => color_t = ColorType::Rgba16,
+                                "RGB32F"  | "rgb32f"  => color_t = ColorType::Rgb32F,
+                                "RGBA32F" | "rgba32f" => color_t = ColorType::Rgba32F,
                                 _ => panic!("unsupported target.pixel \"{}\"", value)
                             }
                             config.target_pixel = color_t;
@@ -133,6 +188,21 @@ pub fn from_file(path: &str) -> ConfigRes {
                             let res = bmx_vec2(value).expect(bmx_failure!(line_counter, line));
                             config.target_resolution = (res.x as u32, res.y as u32);
                         },
+                        "tonemap" => config.target_tonemap = bmx_tonemap(value).expect(bmx_failure!(line_counter, line)),
+                        "exposure" => config.target_exposure = bmx_f64(value).expect(bmx_failure!(line_counter, line)),
+                        "transfer" => config.target_transfer = bmx_transfer(value).expect(bmx_failure!(line_counter, line)),
+                        "transparent_bg" => config.target_transparent_bg = bmx_bool(value).expect(bmx_failure!(line_counter, line)),
+                        "spp_map" => config.target_spp_map = Some(value.trim().to_string()),
+                        "checkpoint" => config.target_checkpoint = Some(value.trim().to_string()),
+                        "checkpoint_interval" => config.target_checkpoint_interval = bmx_duration(value).expect(bmx_failure!(line_counter, line)),
+                        "crop" => config.target_crop = Some(parse_crop(value).expect(bmx_failure!(line_counter, line))),
+                        "crop_output" => {
+                            match value.trim() {
+                                "window" => config.target_crop_full = false,
+                                "full" => config.target_crop_full = true,
+                                _ => panic!("unsupported target.crop_output \"{}\", which must be \"window\" or \"full\"", value)
+                            }
+                        },
                         _ => panic!("unrecognized key \"{}\" in \"target\"!", line)
                     }
                 },
@@ -165,6 +235,15 @@ pub fn from_file(path: &str) -> ConfigRes {
                         "bvh_acc"   => config.renderer_bvh_acc = bmx_bool(value).expect(bmx_failure!(line_counter, line)),
                         "max_depth" => config.renderer_max_depth = bmx_u32(value).expect(bmx_failure!(line_counter, line)),
                         "spp"       => config.renderer_spp = bmx_u32(value).expect(bmx_failure!(line_counter, line)),
+                        "adaptive"  => config.renderer_adaptive = Some(bmx_adaptive(value).expect(bmx_failure!(line_counter, line))),
+                        "tile_size" => config.renderer_tile.size = bmx_u32(value).expect(bmx_failure!(line_counter, line)).max(1),
+                        "tile_order" => config.renderer_tile.order = bmx_tile_order(value).expect(bmx_failure!(line_counter, line)),
+                        "time_limit" => config.renderer_time_limit = Some(bmx_duration(value).expect(bmx_failure!(line_counter, line))),
+                        "progressive" => config.renderer_progressive = Some(bmx_progressive(value).expect(bmx_failure!(line_counter, line))),
+                        "filter"    => config.renderer_filter_name = value.trim().to_lowercase(),
+                        "filter_radius" => filter_radius = Some(bmx_f64(value).expect(bmx_failure!(line_counter, line))),
+                        "sampler"   => config.renderer_sampler_name = value.trim().to_lowercase(),
+                        "seed"      => config.renderer_seed = bmx_u64(value).expect(bmx_failure!(line_counter, line)),
                         _ => panic!("unrecognized key \"{}\" in \"renderer\"!", line)
                     }
                 },
@@ -187,13 +266,38 @@ pub fn from_file(path: &str) -> ConfigRes {
         }
     }
 
+    config.renderer_filter = emit_filter(&config.renderer_filter_name, filter_radius);
+    let sampler_spp = config.renderer_adaptive.map_or(config.renderer_spp, |x| x.max_spp);
+    config.renderer_sampler = emit_sampler(&config.renderer_sampler_name, sampler_spp, config.renderer_seed);
+
+    // strata of stratified sampler depend on spp.
+    let strata = if config.renderer_sampler_name == "stratified" { sampler_spp } else { 0 };
+    let settings = format!(
+        "{:?}|{}|{}|{}|{}|{}|{}|{}|{:?}|{:?}|{}|{:?}|{}",
+        config.target_resolution, config.target_transparent_bg,
+        config.renderer_max_depth, config.renderer_filter_name, config.renderer_filter.radius(),
+        config.renderer_sampler_name, strata, config.renderer_seed,
+        config.camera_pos, config.camera_dir, config.camera_fov, config.camera_viewport,
+        scene_string
+    );
+
     /* Parsing Scene Block */
     if scene_string.trim().is_empty() {
         panic!("field \"scene\" is empty, which is required!");
     }
 
-    let scene_data = jzon::parse(&scene_string)
-                          .expect("failed to parse \"scene\" as Json format!");
+    let mut scene_data = jzon::parse(&scene_string)
+                              .expect("failed to parse \"scene\" as Json format!");
+    let mut textures = Vec::new();
+    resolve_textures(&mut scene_data, Path::new(path).parent().unwrap_or(Path::new("")), &mut textures);
+
+    // edited textures invalidate checkpoints as well, and missing ones fail later when loaded.
+    let mut hashed = settings.into_bytes();
+    for texture in &textures {
+        hashed.extend(fs::read(texture).unwrap_or_default());
+    }
+    config.scene_hash = fnv1a(&hashed);
+
     let scene_object = scene_data.as_object()
                                 .expect("faild to parse \"scene\" as Json::Object!");
 
@@ -228,7 +332,14 @@ pub fn from_file(path: &str) -> ConfigRes {
         let mat = emit_mat(mat_info, Some(entities.len()));
         let mesh = emit_mesh(mesh_info, entities.len());
 
-        entities.push(Entity::new(mat, mesh));
+        let mut object = Entity::new(mat, mesh);
+        if let Some(alpha) = entity.get("alpha") {
+            let alpha = json_tex_param(alpha)
+                            .unwrap_or_else(|err| panic!("failed to parse entity<{}>.alpha: {}", entities.len(), err));
+            object = object.with_alpha(alpha);
+        }
+
+        entities.push(object);
     }
 
     let scene = Scene{ background, entities };
@@ -237,9 +348,34 @@ pub fn from_file(path: &str) -> ConfigRes {
     return config;
 }
 
+/// Parse crop window `(x0, y0, x1, y1)`, where `(x1, y1)` is exclusive.
+/// 
+/// Parentheses are optional, so it can be given by command line as `x0,y0,x1,y1`.
+pub fn parse_crop(value: &str) -> Result<Tile, &'static str> {
+    let pattern = regex!(r"^\(?\s*([0-9]+)\s*,\s*([0-9]+)\s*,\s*([0-9]+)\s*,\s*([0-9]+)\s*\)?$");
+    let Some(res) = pattern.captures(value.trim()) else {
+        return Err("value is not a crop window, which must be \"(x0, y0, x1, y1)\"!");
+    };
+
+    let coord = |i: usize| -> u32 { res.get(i).unwrap().as_str().parse().unwrap() };
+    let window = Tile { min: (coord(1), coord(2)), max: (coord(3), coord(4)) };
+    if window.min.0 >= window.max.0 || window.min.1 >= window.max.1 {
+        return Err("crop window is empty, which requires x0 < x1 and y0 < y1!");
+    }
+
+    Ok(window)
+}
+
 /* INTERNAL FIELD */
 enum Block { None, Target, Renderer, Camera, Scene }
 
+/// 64-bit FNV-1a hash, which is stable across builds.
+fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf2_9ce4_8422_2325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0000_0100_0000_01b3)
+    })
+}
+
 fn bmx_bool(value: &str) -> Result<bool, &'static str> {
     match value.to_lowercase().as_str() {
         "true" => return Ok(true),
@@ -255,6 +391,13 @@ fn bmx_u32(value: &str) -> Result<u32, &'static str> {
     return Ok(num);
 }
 
+fn bmx_u64(value: &str) -> Result<u64, &'static str> {
+    let Ok(num) = value.trim().parse() else {
+        return Err("value is not a non-negative interger!");
+    };
+    Ok(num)
+}
+
 fn bmx_f64(value: &str) -> Result<f64, &'static str> {
     let Ok(num) = value.parse() else {
         return Err("value is not a float number!");
@@ -262,6 +405,140 @@ fn bmx_f64(value: &str) -> Result<f64, &'static str> {
     return Ok(num);
 }
 
+/// Split `{ key: value, ... }` into key-value pairs.
+fn bmx_object(value: &str) -> Result<Vec<(&str, &str)>, &'static str> {
+    let pattern = regex!(r"^\{(.*)\}$");
+    let Some(res) = pattern.captures(value.trim()) else {
+        return Err("value is not an object, which must be \"{ <key>: <value>, ... }\"!");
+    };
+
+    let mut pairs = Vec::new();
+    for item in res.get(1).unwrap().as_str().split(',') {
+        let Some((key, field)) = item.split_once(':') else {
+            return Err("items of object must be \"<key>: <value>\"!");
+        };
+        pairs.push((key.trim(), field.trim()));
+    }
+
+    Ok(pairs)
+}
+
+fn bmx_duration(value: &str) -> Result<Duration, &'static str> {
+    let pattern = regex!(r"^([0-9.]+)\s*(ms|s|m|h)$");
+    let Some(res) = pattern.captures(value.trim()) else {
+        return Err("value is not a duration, which must be a number with unit \"ms\", \"s\", \"m\" or \"h\"!");
+    };
+
+    let Ok(num) = res.get(1).unwrap().as_str().parse::<f64>() else {
+        return Err("value of duration is not a float number!");
+    };
+    let scale = match res.get(2).unwrap().as_str() {
+        "ms" => 1e-3,
+        "s" => 1.0,
+        "m" => 60.0,
+        _ => 3600.0
+    };
+
+    Ok(Duration::from_secs_f64(num * scale))
+}
+
+fn bmx_adaptive(value: &str) -> Result<AdaptiveConfig, &'static str> {
+    let (mut min_spp, mut max_spp, mut threshold) = (None, None, None);
+    for (key, field) in bmx_object(value)? {
+        match key {
+            "min_spp" => min_spp = Some(bmx_u32(field)?),
+            "max_spp" => max_spp = Some(bmx_u32(field)?),
+            "threshold" => threshold = Some(bmx_f64(field)?),
+            _ => return Err("unrecognized key in adaptive config, which must be one of \"min_spp\", \"max_spp\" or \"threshold\"!")
+        }
+    }
+
+    let (Some(min_spp), Some(max_spp), Some(threshold)) = (min_spp, max_spp, threshold) else {
+        return Err("adaptive config requires \"min_spp\", \"max_spp\" and \"threshold\"!");
+    };
+    if min_spp == 0 || max_spp < min_spp {
+        return Err("adaptive config requires 0 < min_spp <= max_spp!");
+    }
+
+    Ok(AdaptiveConfig { min_spp, max_spp, threshold })
+}
+
+fn bmx_progressive(value: &str) -> Result<ProgressiveConfig, &'static str> {
+    let (mut pass_spp, mut interval) = (None, None);
+    for (key, field) in bmx_object(value)? {
+        match key {
+            "pass_spp" => pass_spp = Some(bmx_u32(field)?),
+            "interval" => interval = Some(bmx_duration(field)?),
+            _ => return Err("unrecognized key in progressive config, which must be one of \"pass_spp\" or \"interval\"!")
+        }
+    }
+
+    let Some(pass_spp) = pass_spp else {
+        return Err("progressive config requires \"pass_spp\"!");
+    };
+    if pass_spp == 0 {
+        return Err("pass_spp of progressive config must be bigger than 0!");
+    }
+
+    Ok(ProgressiveConfig { pass_spp, interval })
+}
+
+fn bmx_tile_order(value: &str) -> Result<TileOrder, &'static str> {
+    match value.trim().to_lowercase().as_str() {
+        "scanline" => Ok(TileOrder::Scanline),
+        "spiral" => Ok(TileOrder::Spiral),
+        "hilbert" => Ok(TileOrder::Hilbert),
+        _ => Err("value is not a tile order, which must be one of \"scanline\", \"spiral\" or \"hilbert\"!")
+    }
+}
+
+fn bmx_tonemap(value: &str) -> Result<ToneMapping, &'static str> {
+    match value.trim().to_lowercase().as_str() {
+        "clamp" => Ok(ToneMapping::Clamp),
+        "reinhard" => Ok(ToneMapping::Reinhard),
+        "aces" => Ok(ToneMapping::Aces),
+        "hable" | "uncharted2" => Ok(ToneMapping::Hable),
+        other => {
+            let ext_re = regex!(r"reinhard_ext\(\s*([0-9.]+)\s*\)");
+            let Some(res) = ext_re.captures(other) else {
+                return Err("value is not a tone mapping operator, which must be one of \"clamp\", \"reinhard\", \"reinhard_ext(<white>)\", \"aces\" or \"hable\"!");
+            };
+
+            let Ok(white) = res.get(1).unwrap().as_str().parse::<f64>() else {
+                return Err("white point of \"reinhard_ext\" is not a float number!");
+            };
+            if white <= 0.0 {
+                return Err("white point of \"reinhard_ext\" must be bigger than 0!");
+            }
+
+            Ok(ToneMapping::ReinhardExt(white))
+        }
+    }
+}
+
+fn bmx_transfer(value: &str) -> Result<TransferFunction, &'static str> {
+    match value.trim().to_lowercase().as_str() {
+        "srgb" => Ok(TransferFunction::Srgb),
+        "rec709" => Ok(TransferFunction::Rec709),
+        "linear" => Ok(TransferFunction::Linear),
+        other => {
+            let gamma_re = regex!(r"gamma\(\s*([0-9.]+)\s*\)");
+            let Some(res) = gamma_re.captures(other) else {
+                return Err("value is not a transfer function, which must be one of \"srgb\", \"rec709\", \"gamma(<N>)\" or \"linear\"!");
+            };
+
+            let Ok(gamma) = res.get(1).unwrap().as_str().parse::<f64>() else {
+                return Err("exponent of \"gamma\" is not a float number!");
+            };
+            if gamma <= 0.0 {
+                return Err("exponent of \"gamma\" must be bigger than 0!");
+            }
+
+            Ok(TransferFunction::Gamma(gamma))
+        }
+    }
+}
+
 fn bmx_vec2(value: &str) -> Result<Vec2, &'static str> {
     let pattern = regex!(r"\(\s*([0-9.]+)\s*,\s*([0-9.]+)\s*\)");
 
@@ -299,6 +576,21 @@ fn bmx_vec3(value: &str) -> Result<Vec3, &'static str> {
     return Ok(Vec3 { x, y, z });
 }
 
+fn json_vec2(value: &Array) -> Result<Vec2, &'static str> {
+    if value.len() != 2 {
+        return Err("length is not equal to 2!");
+    }
+
+    let Some(x) = value[0].as_f64() else {
+        return Err("\"x\" component is not a float number!");
+    };
+    let Some(y) = value[1].as_f64() else {
+        return Err("\"y\" component is not a float number!");
+    };
+
+    return Ok(Vec2 { x, y })
+}
+
 fn json_vec3(value: &Array) -> Result<Vec3, &'static str> {
     if value.len() != 3 {
         return Err("length is not equal to 3!");
@@ -317,12 +609,231 @@ fn json_vec3(value: &Array) -> Result<Vec3, &'static str> {
     return Ok(Vec3 { x, y, z })
 }
 
+/// Rewrite relative `"texture"` paths in `value` into paths under `base`, the directory of .cfg file,
+/// and collect the resolved paths into `textures`.
+fn resolve_textures(value: &mut JsonValue, base: &Path, textures: &mut Vec<String>) {
+    for (key, member) in value.entries_mut() {
+        if key == "texture" {
+            if let Some(path) = member.as_str() {
+                let resolved = base.join(path).to_string_lossy().into_owned();
+                textures.push(resolved.clone());
+                *member = resolved.into();
+                continue;
+            }
+        }
+        resolve_textures(member, base, textures);
+    }
+    for member in value.members_mut() {
+        resolve_textures(member, base, textures);
+    }
+}
+
+/// Parse a material parameter, which is either a float number, a Vector3D, an image texture like
+/// `{ "texture": "wood.png", "wrap": "repeat", "srgb": true, "channel": "rgb" }`, or a procedural texture like
+/// `{ "src": "prefab: tex.checker", "args": { .. } }`.
+///
+/// Relative image paths are resolved against the directory of .cfg file by [`resolve_textures`].
+fn json_tex_param(value: &JsonValue) -> Result<TexParam, String> {
+    if let Some(scalar) = value.as_f64() {
+        return Ok(TexParam::from(scalar));
+    }
+    if let Some(array) = value.as_array() {
+        return json_vec3(array).map(TexParam::from).map_err(str::to_string);
+    }
+
+    let Some(object) = value.as_object() else {
+        return Err("value is neither a Vector3D nor a texture!".to_string());
+    };
+    if object.get("src").is_some() {
+        return emit_tex(object);
+    }
+    let Some(path) = object.get("texture").and_then(JsonValue::as_str) else {
+        return Err("\"texture\" is supposed to be the path of image!".to_string());
+    };
+
+    let wrap = match object.get("wrap").map(|w| w.as_str()) {
+        None | Some(Some("repeat")) => prefabs::textures::WrapMode::Repeat,
+        Some(Some("mirror")) => prefabs::textures::WrapMode::Mirror,
+        Some(Some("clamp")) => prefabs::textures::WrapMode::Clamp,
+        _ => return Err("\"wrap\" is supposed to be one of repeat, mirror and clamp!".to_string())
+    };
+    let srgb = match object.get("srgb") {
+        None => true,
+        Some(srgb) => srgb.as_bool().ok_or("\"srgb\" is supposed to be a Json::Boolean!")?
+    };
+
+    let texture = match object.get("channel").map(|c| c.as_str()) {
+        None | Some(Some("rgb")) => prefabs::textures::ImageTexture::load(path, wrap, srgb)?,
+        Some(Some("alpha")) => prefabs::textures::ImageTexture::load_alpha(path, wrap)?,
+        _ => return Err("\"channel\" is supposed to be one of rgb and alpha!".to_string())
+    };
+    return Ok(TexParam::Texture(Rc::new(texture)));
+}
+
+/// Get an optional float argument of texture.
+fn tex_arg_f64(args: &Object, name: &str, default: f64) -> Result<f64, String> {
+    match args.get(name) {
+        None => Ok(default),
+        Some(value) => value.as_f64().ok_or(format!("\"{}\" is supposed to be a float number!", name))
+    }
+}
+
+/// Get an optional parameter argument of texture.
+fn tex_arg_param(args: &Object, name: &str, default: f64) -> Result<TexParam, String> {
+    match args.get(name) {
+        None => Ok(TexParam::from(default)),
+        Some(value) => json_tex_param(value).map_err(|err| format!("\"{}\": {}", name, err))
+    }
+}
+
+fn emit_tex(value: &Object) -> Result<TexParam, String> {
+    let Some(src) = value.get("src").and_then(JsonValue::as_str) else {
+        return Err("\"tex.src\" is supposed to be a Json::Str!".to_string());
+    };
+    let empty = Object::new();
+    let args = match value.get("args") {
+        None => &empty,
+        Some(args) => args.as_object().ok_or("\"tex.args\" is supposed to be a Json::Object!")?
+    };
+
+    let src_pattern = regex!(r"(\w+):\s*([\s\w\/\\.-]+)");
+    let Some(source) = src_pattern.captures(src.trim()) else {
+        return Err("invalid texture source format!".to_string());
+    };
+    if source.get(1).unwrap().as_str() != "prefab" {
+        return Err("only support prefab texture!".to_string());
+    }
+
+    let seed = tex_arg_f64(args, "seed", 0.0)? as u64;
+    let octaves = tex_arg_f64(args, "octaves", 5.0)? as u32;
+
+    let texture: Rc<dyn Texture>;
+    match source.get(2).unwrap().as_str().trim() {
+        "tex.checker" => {
+            let solid = match args.get("solid") {
+                None => false,
+                Some(solid) => solid.as_bool().ok_or("\"solid\" is supposed to be a Json::Boolean!")?
+            };
+
+            texture = Rc::new(prefabs::textures::Checker::new(
+                tex_arg_param(args, "even", 0.0)?,
+                tex_arg_param(args, "odd", 1.0)?,
+                tex_arg_f64(args, "scale", 1.0)?,
+                solid
+            ));
+        },
+        "tex.noise" => {
+            let kind = match args.get("kind").map(|k| k.as_str()) {
+                None | Some(Some("fbm")) => prefabs::textures::NoiseKind::Fbm,
+                Some(Some("perlin")) => prefabs::textures::NoiseKind::Perlin,
+                Some(Some("turbulence")) => prefabs::textures::NoiseKind::Turbulence,
+                _ => return Err("\"kind\" is supposed to be one of perlin, fbm and turbulence!".to_string())
+            };
+
+            texture = Rc::new(prefabs::textures::NoiseTexture::new(
+                seed, kind,
+                tex_arg_f64(args, "scale", 1.0)?,
+                octaves,
+                tex_arg_param(args, "low", 0.0)?,
+                tex_arg_param(args, "high", 1.0)?
+            ));
+        },
+        "tex.marble" => {
+            texture = Rc::new(prefabs::textures::Marble::new(
+                seed,
+                tex_arg_f64(args, "scale", 1.0)?,
+                tex_arg_f64(args, "distortion", 5.0)?,
+                octaves,
+                tex_arg_param(args, "base", 1.0)?,
+                tex_arg_param(args, "vein", 0.0)?
+            ));
+        },
+        "tex.wood" => {
+            texture = Rc::new(prefabs::textures::Wood::new(
+                seed,
+                tex_arg_f64(args, "rings", 8.0)?,
+                tex_arg_f64(args, "distortion", 0.5)?,
+                tex_arg_param(args, "light", 1.0)?,
+                tex_arg_param(args, "dark", 0.0)?
+            ));
+        },
+        src => return Err(format!("unrecognized texture \"{}\"!", src))
+    }
+
+    return Ok(TexParam::Texture(texture));
+}
+
+/// Parse `roughness` of microfacet material, or `roughness_u` and `roughness_v` if it is anisotropic.
+fn json_roughness(args: &Object, material: &str, location: &str) -> (TexParam, TexParam) {
+    let param = |name: &str, default: Option<&JsonValue>| -> TexParam {
+        match args.get(name).or(default) {
+            None => TexParam::from(0.5),
+            Some(value) => json_tex_param(value)
+                            .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.{}.{}\": {} (at {})", material, name, err, location))
+        }
+    };
+
+    let roughness = args.get("roughness");
+    (param("roughness_u", roughness), param("roughness_v", roughness))
+}
+
 fn emit_mat(value: &Object, index: Option<usize>) -> Rc<dyn Material> {
     let location = match index {
         Some(i) => &format!("scene.entity<{}>", i),
         None => "scene.background"
     };
 
+    return emit_mat_at(value, location);
+}
+
+/// Parse IOR, which is a float number, a glass preset like `"bk7"`,
+/// `{ "cauchy": [a, b] }` or `{ "sellmeier": { "b": [b1, b2, b3], "c": [c1, c2, c3] } }`.
+fn json_ior(value: &JsonValue) -> Result<prefabs::materials::Ior, String> {
+    if let Some(ior) = value.as_f64() {
+        return Ok(prefabs::materials::Ior::Constant(ior));
+    }
+    if let Some(name) = value.as_str() {
+        return prefabs::materials::Ior::preset(name)
+                    .ok_or(format!("unrecognized glass \"{}\", supposed to be one of bk7 and sf11!", name));
+    }
+
+    let coefficients = |value: Option<&JsonValue>, n: usize| -> Result<Vec<f64>, String> {
+        let values = value.and_then(JsonValue::as_array)
+                          .filter(|values| values.len() == n)
+                          .ok_or(format!("coefficients are supposed to be an array of {} float numbers!", n))?;
+        values.iter()
+              .map(|v| v.as_f64().ok_or(format!("coefficients are supposed to be an array of {} float numbers!", n)))
+              .collect()
+    };
+
+    let Some(object) = value.as_object() else {
+        return Err("value is supposed to be a float number, a glass preset, or an object of coefficients!".to_string());
+    };
+    if object.get("cauchy").is_some() {
+        let ab = coefficients(object.get("cauchy"), 2)?;
+        return Ok(prefabs::materials::Ior::Cauchy { a: ab[0], b: ab[1] });
+    }
+    if let Some(sellmeier) = object.get("sellmeier").and_then(JsonValue::as_object) {
+        let b = coefficients(sellmeier.get("b"), 3)?;
+        let c = coefficients(sellmeier.get("c"), 3)?;
+        return Ok(prefabs::materials::Ior::Sellmeier { b: [b[0], b[1], b[2]], c: [c[0], c[1], c[2]] });
+    }
+
+    return Err("expected \"cauchy\" or \"sellmeier\" coefficients!".to_string());
+}
+
+/// Emit a material object nested in `args` of combinator `material`.
+fn emit_nested_mat(args: &Object, name: &str, material: &str, location: &str) -> Rc<dyn Material> {
+    let value = args.get(name)
+                    .expect(&format!("\"prefab.mat.{}.{}\" is missing (at {})!", material, name, location))
+                    .as_object()
+                    .expect(&format!("\"prefab.mat.{}.{}\" is supposed to be a material object (at {})!", material, name, location));
+
+    return emit_mat_at(value, &format!("{}.mat.{}", location, name));
+}
+
+/// Emit material at `location`, which may nest other materials inside its arguments.
+fn emit_mat_at(value: &Object, location: &str) -> Rc<dyn Material> {
     let src = value.get("src")
                    .expect(&format!("missing material source (at {})!", location))
                    .as_str()
@@ -358,32 +869,204 @@ fn emit_mat(value: &Object, index: Option<usize>) -> Rc<dyn Material> {
         },
         "mat.lambertian" => {
             let albedo_arg = args.get("albedo")
-                                .expect(&format!("\"prefab.mat.lambertain.albedo\" is missing (at {})!", location))
-                                .as_array()
-                                .expect(&format!("\"prefab.mat.lambertain.albedo\" is supposed to be a Vector3D (at {})!", location));
+                                .expect(&format!("\"prefab.mat.lambertain.albedo\" is missing (at {})!", location));
             
-            let albedo = json_vec3(albedo_arg)
-                                .expect(&format!("failed to parse \"prefab.mat.lambertain.albedo\" as Vector3D (at {})", location));
+            let albedo = json_tex_param(albedo_arg)
+                                .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.lambertain.albedo\": {} (at {})", err, location));
             
             material = Rc::new(prefabs::materials::Lambertian::new(albedo));
         },
+        "mat.oren_nayar" => {
+            let albedo = json_tex_param(args.get("albedo").expect(&format!("\"prefab.mat.oren_nayar.albedo\" is missing (at {})!", location)))
+                            .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.oren_nayar.albedo\": {} (at {})", err, location));
+            // sigma is given in degrees.
+            let sigma = args.get("sigma")
+                            .map(|sigma| sigma.as_f64().expect(&format!("\"prefab.mat.oren_nayar.sigma\" is supposed to be a float number (at {})!", location)))
+                            .unwrap_or(20.0);
+
+            material = Rc::new(prefabs::materials::OrenNayar::new(albedo, sigma.to_radians()));
+        },
+        "mat.rough_conductor" => {
+            let (eta, k) = match args.get("metal") {
+                Some(metal) => {
+                    let name = metal.as_str()
+                                    .expect(&format!("\"prefab.mat.rough_conductor.metal\" is supposed to be a Json::Str (at {})!", location));
+                    prefabs::materials::RoughConductor::preset(name)
+                        .unwrap_or_else(|| panic!("unrecognized metal \"{}\", supposed to be one of gold, copper, aluminium and silver (at {})!", name, location))
+                },
+                None => {
+                    let complex_ior = |name: &str| -> Vec3 {
+                        let arg = args.get(name)
+                                      .and_then(JsonValue::as_array)
+                                      .unwrap_or_else(|| panic!("\"prefab.mat.rough_conductor.{}\" is supposed to be a Vector3D if \"metal\" is missing (at {})!", name, location));
+                        json_vec3(arg)
+                            .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.rough_conductor.{}\": {} (at {})", name, err, location))
+                    };
+                    (complex_ior("eta"), complex_ior("k"))
+                }
+            };
+            let (roughness_u, roughness_v) = json_roughness(args, "rough_conductor", location);
+
+            material = Rc::new(prefabs::materials::RoughConductor::new(eta, k, roughness_u, roughness_v));
+        },
+        "mat.rough_dielectric" => {
+            let ior = args.get("ior")
+                          .map(|ior| ior.as_f64().expect(&format!("\"prefab.mat.rough_dielectric.ior\" is supposed to be a float number (at {})!", location)))
+                          .unwrap_or(1.5);
+            let (roughness_u, roughness_v) = json_roughness(args, "rough_dielectric", location);
+
+            material = Rc::new(prefabs::materials::RoughDielectric::new(ior, roughness_u, roughness_v));
+        },
+        "mat.principled" => {
+            let param = |name: &str| -> Option<TexParam> {
+                args.get(name).map(|value| {
+                    json_tex_param(value)
+                        .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.principled.{}\": {} (at {})", name, err, location))
+                })
+            };
+
+            let mut principled = prefabs::materials::Principled::new(param("base_color").unwrap_or(TexParam::from(0.8)));
+            if let Some(metallic) = param("metallic") { principled.metallic = metallic; }
+            if let Some(roughness) = param("roughness") { principled.roughness = roughness; }
+            if let Some(specular) = param("specular") { principled.specular = specular; }
+            if let Some(sheen) = param("sheen") { principled.sheen = sheen; }
+            if let Some(clearcoat) = param("clearcoat") { principled.clearcoat = clearcoat; }
+            if let Some(clearcoat_roughness) = param("clearcoat_roughness") { principled.clearcoat_roughness = clearcoat_roughness; }
+            if let Some(transmission) = param("transmission") { principled.transmission = transmission; }
+            if let Some(emission) = param("emission") { principled.emission = emission; }
+            if let Some(ior) = args.get("ior") {
+                principled.ior = ior.as_f64()
+                                    .expect(&format!("\"prefab.mat.principled.ior\" is supposed to be a float number (at {})!", location));
+            }
+
+            material = Rc::new(principled);
+        },
+        "mat.dielectric" => {
+            let ior = match args.get("ior") {
+                None => prefabs::materials::Ior::Constant(1.5),
+                Some(ior) => json_ior(ior)
+                                .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.dielectric.ior\": {} (at {})", err, location))
+            };
+
+            material = Rc::new(prefabs::materials::Dielectric::new(ior));
+        },
+        "mat.thin_film" => {
+            let thickness = args.get("thickness")
+                                .map(|value| {
+                                    json_tex_param(value)
+                                        .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.thin_film.thickness\": {} (at {})", err, location))
+                                })
+                                .unwrap_or(TexParam::from(400.0));
+            let film_ior = args.get("film_ior")
+                               .map(|ior| ior.as_f64().expect(&format!("\"prefab.mat.thin_film.film_ior\" is supposed to be a float number (at {})!", location)))
+                               .unwrap_or(1.33);
+            let ior = args.get("ior")
+                          .map(|ior| ior.as_f64().expect(&format!("\"prefab.mat.thin_film.ior\" is supposed to be a float number (at {})!", location)))
+                          .unwrap_or(1.0);
+
+            material = Rc::new(prefabs::materials::ThinFilm::new(thickness, film_ior, ior));
+        },
+        "mat.mix" => {
+            let first = emit_nested_mat(args, "first", "mix", location);
+            let second = emit_nested_mat(args, "second", "mix", location);
+            let weight = json_tex_param(args.get("weight").expect(&format!("\"prefab.mat.mix.weight\" is missing (at {})!", location)))
+                            .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.mix.weight\": {} (at {})", err, location));
+
+            material = Rc::new(prefabs::materials::Mix::new(first, second, weight));
+        },
+        "mat.coated" => {
+            let base = emit_nested_mat(args, "base", "coated", location);
+            let ior = args.get("ior")
+                          .map(|ior| ior.as_f64().expect(&format!("\"prefab.mat.coated.ior\" is supposed to be a float number (at {})!", location)))
+                          .unwrap_or(1.5);
+            let roughness = args.get("roughness")
+                                .map(|value| {
+                                    json_tex_param(value)
+                                        .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.coated.roughness\": {} (at {})", err, location))
+                                })
+                                .unwrap_or(TexParam::from(0.05));
+
+            let mut coated = prefabs::materials::Coated::new(base, ior, roughness);
+            if let Some(tint) = args.get("tint") {
+                coated.tint = json_tex_param(tint)
+                                .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.coated.tint\": {} (at {})", err, location));
+            }
+
+            material = Rc::new(coated);
+        },
+        "mat.two_sided" => {
+            let front = emit_nested_mat(args, "front", "two_sided", location);
+            let back = emit_nested_mat(args, "back", "two_sided", location);
+
+            material = Rc::new(prefabs::materials::TwoSided::new(front, back));
+        },
         "mat.emissive" => {
             let emissive_arg = args.get("emissive")
-                                .expect(&format!("\"prefab.mat.emissive.emissive\" is missing (at {})!", location))
-                                .as_array()
-                                .expect(&format!("\"prefab.mat.emissive.emissive\" is supposed to be a Vector3D (at {})!", location));
+                                .expect(&format!("\"prefab.mat.emissive.emissive\" is missing (at {})!", location));
             
-            let emissive = json_vec3(emissive_arg)
-                                .expect(&format!("failed to parse \"prefab.mat.emissive.emissive\" as Vector3D (at {})", location));
+            let emissive = json_tex_param(emissive_arg)
+                                .unwrap_or_else(|err| panic!("failed to parse \"prefab.mat.emissive.emissive\": {} (at {})", err, location));
             
             material = Rc::new(prefabs::materials::Emissive::new(emissive));
         },
         src => panic!("unrecognized material \"{}\" (at {})!", src, location),
     }
 
+    let mut material = material;
+    if let Some(map_arg) = value.get("normal_map") {
+        let map = json_tex_param(map_arg)
+                    .unwrap_or_else(|err| panic!("failed to parse \"mat.normal_map\": {} (at {})", err, location));
+        let strength = value.get("normal_strength")
+                            .map(|s| s.as_f64().expect(&format!("\"mat.normal_strength\" is supposed to be a float number (at {})!", location)))
+                            .unwrap_or(1.0);
+
+        material = Rc::new(prefabs::materials::NormalMap::new(material, map, strength));
+    }
+    if let Some(height_arg) = value.get("bump_map") {
+        let height = json_tex_param(height_arg)
+                        .unwrap_or_else(|err| panic!("failed to parse \"mat.bump_map\": {} (at {})", err, location));
+        let scale = value.get("bump_scale")
+                         .map(|s| s.as_f64().expect(&format!("\"mat.bump_scale\" is supposed to be a float number (at {})!", location)))
+                         .unwrap_or(1.0);
+
+        material = Rc::new(prefabs::materials::BumpMap::new(material, height, scale));
+    }
+
     return material;
 }
 
+fn emit_filter(name: &str, radius: Option<f64>) -> Rc<dyn Filter> {
+    // narrower filters leave pixels between samples without any weight.
+    if radius.is_some_and(|r| r < 0.5) {
+        panic!("renderer.filter_radius must be at least 0.5!");
+    }
+
+    let filter: Rc<dyn Filter>;
+    match name {
+        "box" => filter = Rc::new(prefabs::filters::BoxFilter::new(radius.unwrap_or(0.5))),
+        "tent" => filter = Rc::new(prefabs::filters::TentFilter::new(radius.unwrap_or(1.0))),
+        "gaussian" => filter = Rc::new(prefabs::filters::GaussianFilter::new(radius.unwrap_or(1.5))),
+        "mitchell" => filter = Rc::new(prefabs::filters::MitchellFilter::new(radius.unwrap_or(2.0))),
+        "lanczos" => filter = Rc::new(prefabs::filters::LanczosFilter::new(radius.unwrap_or(3.0))),
+        _ => panic!("unrecognized renderer.filter \"{}\", which must be one of \"box\", \"tent\", \"gaussian\", \"mitchell\" or \"lanczos\"!", name)
+    }
+
+    return filter;
+}
+
+fn emit_sampler(name: &str, spp: u32, seed: u64) -> Box<dyn Sampler> {
+    let sampler: Box<dyn Sampler>;
+    match name {
+        "independent" => sampler = Box::new(prefabs::samplers::IndependentSampler::new(seed)),
+        "stratified" => sampler = Box::new(prefabs::samplers::StratifiedSampler::new(spp, seed)),
+        "halton" => sampler = Box::new(prefabs::samplers::HaltonSampler::new(seed)),
+        "sobol" => sampler = Box::new(prefabs::samplers::SobolSampler::new(seed)),
+        _ => panic!("unrecognized renderer.sampler \"{}\", which must be one of \"independent\", \"stratified\", \"halton\" or \"sobol\"!", name)
+    }
+
+    return sampler;
+}
+
 fn emit_mesh(value: &Object, index: usize) -> Rc<dyn Hittable> {
     let location = format!("scene.entity<{}>", index);
 
@@ -422,6 +1105,67 @@ fn emit_mesh(value: &Object, index: usize) -> Rc<dyn Hittable> {
                     
                     mesh = Rc::new(prefabs::shapes::Sphere::new(center, radius));
                 },
+                "shape.quad" => {
+                    let vector = |name: &str| -> Vec3 {
+                        let arg = args.get(name)
+                                      .expect(&format!("\"prefab.shape.quad.{}\" is missing (at {})!", name, location))
+                                      .as_array()
+                                      .expect(&format!("\"prefab.shape.quad.{}\" is supposed to be a Vector3D (at {})!", name, location));
+                        json_vec3(arg)
+                            .expect(&format!("\"prefab.shape.quad.{}\" is supposed to be a Vector3D (at {})!", name, location))
+                    };
+
+                    mesh = Rc::new(prefabs::shapes::Quad::new(vector("corner"), vector("u"), vector("v")));
+                },
+                "shape.triangle" => {
+                    let vertices_arg = args.get("vertices")
+                                           .expect(&format!("\"prefab.shape.triangle.vertices\" is missing (at {})!", location))
+                                           .as_array()
+                                           .expect(&format!("\"prefab.shape.triangle.vertices\" is supposed to be an array of 3 Vector3D (at {})!", location));
+                    if vertices_arg.len() != 3 {
+                        panic!("\"prefab.shape.triangle.vertices\" is supposed to be an array of 3 Vector3D (at {})!", location);
+                    }
+
+                    let vertices = [0, 1, 2].map(|i| {
+                        vertices_arg[i].as_array()
+                                       .and_then(|v| json_vec3(v).ok())
+                                       .expect(&format!("\"prefab.shape.triangle.vertices<{}>\" is supposed to be a Vector3D (at {})!", i, location))
+                    });
+
+                    let uvs = args.get("uvs").map(|uvs_arg| {
+                        let uvs_arg = uvs_arg.as_array()
+                                             .filter(|uvs| uvs.len() == 3)
+                                             .expect(&format!("\"prefab.shape.triangle.uvs\" is supposed to be an array of 3 Vector2D (at {})!", location));
+
+                        [0, 1, 2].map(|i| {
+                            uvs_arg[i].as_array()
+                                      .and_then(|v| json_vec2(v).ok())
+                                      .expect(&format!("\"prefab.shape.triangle.uvs<{}>\" is supposed to be a Vector2D (at {})!", i, location))
+                        })
+                    });
+
+                    let normals = args.get("normals").map(|normals_arg| {
+                        let normals_arg = normals_arg.as_array()
+                                                     .filter(|normals| normals.len() == 3)
+                                                     .expect(&format!("\"prefab.shape.triangle.normals\" is supposed to be an array of 3 Vector3D (at {})!", location));
+
+                        [0, 1, 2].map(|i| {
+                            normals_arg[i].as_array()
+                                          .and_then(|v| json_vec3(v).ok())
+                                          .expect(&format!("\"prefab.shape.triangle.normals<{}>\" is supposed to be a Vector3D (at {})!", i, location))
+                        })
+                    });
+
+                    let mut triangle = match uvs {
+                        Some(uvs) => prefabs::shapes::Triangle::with_uvs(vertices, uvs),
+                        None => prefabs::shapes::Triangle::new(vertices)
+                    };
+                    if let Some(normals) = normals {
+                        triangle = triangle.with_normals(normals);
+                    }
+
+                    mesh = Rc::new(triangle);
+                },
                 src => panic!("unrecognized mesh source \"{}\" (at {})!", src, location)
             }
         },
@@ -0,0 +1,137 @@
+//! Save and load the accumulation state of rendering.
+//!
+//! Samplers are deterministic for every (pixel, sample index),
+//! so the per-pixel sample counts are also the indices to continue sampling from.
+use crate::{ FrameBuffer, math::Vec3 };
+use std::fs;
+
+/// Accumulation state of an unfinished or finished rendering.
+pub struct Checkpoint {
+    /// Hash of the settings affecting accumulation, see [`scene_hash`](crate::utils::cfg_loader::ConfigRes::scene_hash).
+    pub scene_hash: u64,
+    /// Accumulated sums and sample counts.
+    pub framebuffer: FrameBuffer
+}
+
+/// Write checkpoint into file `name`.
+///
+/// The checkpoint is written aside and renamed,
+/// so the previous one stays valid if writing is interrupted.
+pub fn save(name: &str, checkpoint: &Checkpoint) -> Result<(), String> {
+    let fb = &checkpoint.framebuffer;
+    let mut data = Vec::with_capacity(HEADER_LEN + fb.len() * PIXEL_LEN);
+
+    data.extend_from_slice(MAGIC);
+    data.extend_from_slice(&VERSION.to_le_bytes());
+    data.extend_from_slice(&checkpoint.scene_hash.to_le_bytes());
+    data.extend_from_slice(&fb.size.0.to_le_bytes());
+    data.extend_from_slice(&fb.size.1.to_le_bytes());
+    data.extend_from_slice(&fb.offset.0.to_le_bytes());
+    data.extend_from_slice(&fb.offset.1.to_le_bytes());
+
+    for i in 0..fb.len() {
+        let color = fb.color_sum[i];
+        for value in [color.x, color.y, color.z, fb.alpha_sum[i], fb.weight_sum[i], fb.luminance_sum[i], fb.luminance_sq_sum[i]] {
+            data.extend_from_slice(&value.to_le_bytes());
+        }
+        data.extend_from_slice(&fb.sample_count[i].to_le_bytes());
+    }
+
+    let partial = format!("{}.part", name);
+    fs::write(&partial, &data).map_err(|err| format!("failed to write checkpoint \"{}\": {}", partial, err))?;
+    fs::rename(&partial, name).map_err(|err| format!("failed to write checkpoint \"{}\": {}", name, err))
+}
+
+/// Read checkpoint from file `name`.
+pub fn load(name: &str) -> Result<Checkpoint, String> {
+    let data = fs::read(name)
+        .map_err(|err| format!("failed to read checkpoint \"{}\": {}", name, err))?;
+
+    if data.len() < HEADER_LEN || &data[0..4] != MAGIC {
+        return Err(format!("\"{}\" is not a checkpoint file", name));
+    }
+
+    let mut reader = Reader { data: &data, offset: 4 };
+    let version = reader.u32();
+    if version != VERSION {
+        return Err(format!("checkpoint version {} is not supported", version));
+    }
+
+    let scene_hash = reader.u64();
+    let size = (reader.u32(), reader.u32());
+    let offset = (reader.u32(), reader.u32());
+    let len = size.0 as usize * size.1 as usize;
+    if data.len() != HEADER_LEN + len * PIXEL_LEN {
+        return Err(format!("checkpoint \"{}\" is truncated or corrupted", name));
+    }
+
+    let mut framebuffer = FrameBuffer::with_offset(size, offset);
+    for i in 0..len {
+        framebuffer.color_sum[i] = Vec3::new(reader.f64(), reader.f64(), reader.f64());
+        framebuffer.alpha_sum[i] = reader.f64();
+        framebuffer.weight_sum[i] = reader.f64();
+        framebuffer.luminance_sum[i] = reader.f64();
+        framebuffer.luminance_sq_sum[i] = reader.f64();
+        framebuffer.sample_count[i] = reader.u32();
+    }
+
+    Ok(Checkpoint { scene_hash, framebuffer })
+}
+
+/* INTERNAL FIELD */
+
+const MAGIC: &[u8; 4] = b"RTCK";
+const VERSION: u32 = 2;
+/// magic, version, scene hash, size and offset.
+const HEADER_LEN: usize = 4 + 4 + 8 + 4 + 4 + 4 + 4;
+/// 7 sums and the sample count.
+const PIXEL_LEN: usize = 7 * 8 + 4;
+
+struct Reader<'a> {
+    data: &'a [u8],
+    offset: usize
+}
+
+impl Reader<'_> {
+    fn take<const N: usize>(&mut self) -> [u8; N] {
+        let bytes = self.data[self.offset..self.offset + N].try_into().unwrap();
+        self.offset += N;
+        bytes
+    }
+
+    fn u32(&mut self) -> u32 { u32::from_le_bytes(self.take()) }
+    fn u64(&mut self) -> u64 { u64::from_le_bytes(self.take()) }
+    fn f64(&mut self) -> f64 { f64::from_le_bytes(self.take()) }
+}
+
+#[cfg(test)]
+mod checkpoint_tests {
+    use super::{ save, load, Checkpoint };
+    use crate::{ FrameBuffer, math::Vec3 };
+
+    #[test]
+    fn roundtrip() {
+        let mut framebuffer = FrameBuffer::with_offset((3, 2), (5, 1));
+        framebuffer.color_sum[4] = Vec3::new(0.25, 1.5, 3.0);
+        framebuffer.alpha_sum[4] = 0.5;
+        framebuffer.weight_sum[4] = 2.0;
+        framebuffer.sample_count[4] = 7;
+        framebuffer.luminance_sum[4] = 1.25;
+        framebuffer.luminance_sq_sum[4] = 0.75;
+
+        let name = std::env::temp_dir().join("raytracing_checkpoint_roundtrip.ckpt");
+        let name = name.to_str().unwrap();
+        save(name, &Checkpoint { scene_hash: 0xdead_beef, framebuffer: framebuffer.clone() }).unwrap();
+        let loaded = load(name).unwrap();
+        std::fs::remove_file(name).unwrap();
+
+        assert_eq!(loaded.scene_hash, 0xdead_beef);
+        assert_eq!(loaded.framebuffer.region(), framebuffer.region());
+        assert_eq!(loaded.framebuffer.color_sum, framebuffer.color_sum);
+        assert_eq!(loaded.framebuffer.alpha_sum, framebuffer.alpha_sum);
+        assert_eq!(loaded.framebuffer.weight_sum, framebuffer.weight_sum);
+        assert_eq!(loaded.framebuffer.sample_count, framebuffer.sample_count);
+        assert_eq!(loaded.framebuffer.luminance_sum, framebuffer.luminance_sum);
+        assert_eq!(loaded.framebuffer.luminance_sq_sum, framebuffer.luminance_sq_sum);
+    }
+}
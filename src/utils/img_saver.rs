@@ -1,67 +1,668 @@
 //! Save image file from pixels buffer.
-use crate::math::{ Vec3, Interval };
-use std::ptr::slice_from_raw_parts;
-use image::{ self, ColorType };
+use crate::{ FrameBuffer, math::Vec3 };
+use std::{ fs::File, io::BufWriter, path::Path };
+use image::{
+    self, ColorType, DynamicImage, ImageBuffer,
+    Luma, LumaA, Rgb, Rgba,
+    RgbImage, RgbaImage, Rgb32FImage, Rgba32FImage
+};
 
-/// Save buffer as image.
+/// Tone mapping operator, which compresses HDR radiance into display range.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ToneMapping {
+    /// Hard-clamp each channel into `[0, 1]`.
+    Clamp,
+    /// Reinhard operator: `c / (1 + c)`.
+    Reinhard,
+    /// Extended Reinhard operator with the given white point,
+    /// which is the smallest radiance mapped to pure white.
+    ReinhardExt(f64),
+    /// ACES filmic curve (Narkowicz's fitting).
+    Aces,
+    /// Hable's filmic curve used in *Uncharted 2*.
+    Hable
+}
+
+/// Output transfer function (OETF), which encodes linear light into signal values.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TransferFunction {
+    /// Piecewise sRGB curve (IEC 61966-2-1).
+    Srgb,
+    /// ITU-R BT.709 camera curve.
+    Rec709,
+    /// Pure power curve with the given gamma, aka. `c^(1/gamma)`.
+    Gamma(f64),
+    /// No encoding, linear values are written as they are.
+    Linear
+}
+
+/// Post-processing configurations applied before quantization.
+#[derive(Debug, Clone, Copy)]
+pub struct PostConfig {
+    /// Exposure compensation in EV, radiance is scaled by `2^exposure`.
+    pub exposure: f64,
+    /// Tone mapping operator.
+    pub tonemap: ToneMapping,
+    /// Transfer function of output signal.
+    ///
+    /// **NOTE:** it is sRGB by default, while earlier versions encoded with `sqrt`, aka. `Gamma(2.0)`,
+    /// which renders shadows darker. Use `Gamma(2.0)` to reproduce those images.
+    pub transfer: TransferFunction
+}
+
+impl Default for PostConfig {
+    fn default() -> Self {
+        PostConfig {
+            exposure: 0.0,
+            tonemap: ToneMapping::Clamp,
+            transfer: TransferFunction::Srgb
+        }
+    }
+}
+
+impl ToneMapping {
+    /// Map a linear radiance component into `[0, 1]`.
+    pub fn apply(&self, comp: f64) -> f64 {
+        let comp = comp.max(0.0);
+
+        let mapped = match *self {
+            ToneMapping::Clamp => comp,
+            ToneMapping::Reinhard => comp / (1.0 + comp),
+            ToneMapping::ReinhardExt(white) => {
+                comp * (1.0 + comp / (white * white)) / (1.0 + comp)
+            },
+            ToneMapping::Aces => {
+                (comp * (2.51 * comp + 0.03)) / (comp * (2.43 * comp + 0.59) + 0.14)
+            },
+            ToneMapping::Hable => {
+                // Hable's curve expects an exposure bias of 2.0 and a linear white point of 11.2.
+                Self::hable_partial(2.0 * comp) / Self::hable_partial(11.2)
+            }
+        };
+
+        mapped.clamp(0.0, 1.0)
+    }
+
+    fn hable_partial(x: f64) -> f64 {
+        let (a, b, c, d, e, f) = (0.15, 0.50, 0.10, 0.20, 0.02, 0.30);
+        ((x * (a * x + c * b) + d * e) / (x * (a * x + b) + d * f)) - e / f
+    }
+}
+
+impl TransferFunction {
+    /// Encode a linear component in `[0, 1]` into signal value.
+    pub fn encode(&self, comp: f64) -> f64 {
+        let comp = comp.clamp(0.0, 1.0);
+
+        match *self {
+            TransferFunction::Srgb => {
+                if comp <= 0.0031308 { 12.92 * comp }
+                else { 1.055 * comp.powf(1.0 / 2.4) - 0.055 }
+            },
+            TransferFunction::Rec709 => {
+                if comp < 0.018 { 4.5 * comp }
+                else { 1.099 * comp.powf(0.45) - 0.099 }
+            },
+            TransferFunction::Gamma(gamma) => comp.powf(1.0 / gamma),
+            TransferFunction::Linear => comp
+        }
+    }
+
+    /// Decode a signal value in `[0, 1]` back into linear component,
+    /// which is the inverse of [`TransferFunction::encode`].
+    pub fn decode(&self, signal: f64) -> f64 {
+        let signal = signal.clamp(0.0, 1.0);
+
+        match *self {
+            TransferFunction::Srgb => {
+                if signal <= 0.04045 { signal / 12.92 }
+                else { ((signal + 0.055) / 1.055).powf(2.4) }
+            },
+            TransferFunction::Rec709 => {
+                if signal < 0.081 { signal / 4.5 }
+                else { ((signal + 0.099) / 1.099).powf(1.0 / 0.45) }
+            },
+            TransferFunction::Gamma(gamma) => signal.powf(gamma),
+            TransferFunction::Linear => signal
+        }
+    }
+
+    /// The encoding exponent stored in PNG `gAMA` chunk.
+    fn png_gamma(&self) -> f64 {
+        match *self {
+            // the value PNG specification (section 11.3.3.5) requires alongside `sRGB` chunk.
+            TransferFunction::Srgb => 1.0 / 2.2,
+            // decoders apply gAMA as the whole curve, so the power curve best fitting the piecewise one
+            // (least squares over 8-bit signals) is written, rather than the `0.45` of its power segment.
+            TransferFunction::Rec709 => 1.0 / 1.95,
+            TransferFunction::Gamma(gamma) => 1.0 / gamma,
+            TransferFunction::Linear => 1.0
+        }
+    }
+}
+
+/// Save buffer as image, whose format is deduced from the extension of `name`.
+/// 
+/// - Color targets without alpha are composited over black,
+///   while targets with alpha keep straight color and alpha.
+/// - Grayscale targets store the luminance of pixels.
+/// - Float targets (`Rgb32F` / `Rgba32F`) store linear radiance with exposure only,
+///   which skips tone mapping and transfer function.
+/// 
+/// PNG files are tagged with colour space chunks according to `post.transfer`:
+/// - `sRGB` for [`TransferFunction::Srgb`].
+/// - `gAMA`, `cHRM` and an `iCCP` profile with BT.709 primaries for the others,
+///   while grayscale ones only get `gAMA`.
 pub fn save_as(
-    name: &str, 
-    buffer: &Vec<Vec3>, 
-    color_t: ColorType, 
-    width: u32, 
-    height: u32
+    name: &str,
+    buffer: &FrameBuffer,
+    post: PostConfig,
+    color_t: ColorType
 ) -> Result<(), String> {
-    // Do Gamma correction.
-    let linear_to_gamma = |comp: f64| -> f64 {
-        if comp <= 0.0 { return 0.0 }
-        return comp.sqrt();
-    };
+    let image = to_image(buffer, post, color_t)?;
 
-    let pixels = buffer.iter().map(|v| {
-        let x = linear_to_gamma(v.x);
-        let y = linear_to_gamma(v.y);
-        let z = linear_to_gamma(v.z);
-        Vec3 { x, y, z }
+    let is_png = Path::new(name).extension()
+                                .is_some_and(|ext| ext.eq_ignore_ascii_case("png"));
+
+    if is_png {
+        return write_png(name, &image, post.transfer);
+    }
+
+    return image.save(name).map_err(|err| format!("{:?}", err));
+}
+
+/// Save per-pixel sample counts of buffer as a heatmap.
+/// 
+/// Counts are normalized by the maximum count of buffer,
+/// and mapped from black (no sample) through red and yellow to white (maximum).
+pub fn save_spp_map(name: &str, buffer: &FrameBuffer) -> Result<(), String> {
+    let (width, height) = buffer.size;
+    let max_count = buffer.sample_count.iter().copied().max().unwrap_or(0).max(1) as f64;
+
+    let image = RgbImage::from_fn(width, height, |x, y| {
+        let t = buffer.sample_count[(y * width + x) as usize] as f64 / max_count;
+        let heat = Vec3::new(3.0 * t, 3.0 * t - 1.0, 3.0 * t - 2.0);
+        let to_u8 = |comp: f64| -> u8 { (u8::MAX as f64 * comp.clamp(0.0, 1.0)).round() as u8 };
+
+        Rgb([to_u8(heat.x), to_u8(heat.y), to_u8(heat.z)])
     });
 
-    // Transfer pixels into Vec<u8>.
-    let intensity = Interval::new(0.0, 0.999);
-    match color_t {
-        ColorType::Rgb8 => {
-            let mut buf = Vec::with_capacity(pixels.len() * 3);
+    image.save(name).map_err(|err| format!("{:?}", err))
+}
+
+/* INTERNAL FIELD */
+
+/// Convert framebuffer into typed image of `color_t`.
+fn to_image(buffer: &FrameBuffer, post: PostConfig, color_t: ColorType) -> Result<DynamicImage, String> {
+    let (width, height) = buffer.size;
+    let exposure_scale = post.exposure.exp2();
+
+    let display = |comp: f64| -> f64 {
+        post.transfer.encode(post.tonemap.apply(exposure_scale * comp))
+    };
+    let luminance = |color: Vec3| -> f64 {
+        0.2126 * color.x + 0.7152 * color.y + 0.0722 * color.z
+    };
+    let to_u8 = |comp: f64| -> u8 { (u8::MAX as f64 * comp.clamp(0.0, 1.0)).round() as u8 };
+    let to_u16 = |comp: f64| -> u16 { (u16::MAX as f64 * comp.clamp(0.0, 1.0)).round() as u16 };
+
+    let index = |x: u32, y: u32| -> usize { (y * width + x) as usize };
+    // premultiplied color, which is composited over black.
+    let opaque = |x: u32, y: u32| -> Vec3 { buffer.color(index(x, y)) };
+    let straight = |x: u32, y: u32| -> (Vec3, f64) {
+        let i = index(x, y);
+        (buffer.straight_color(i), buffer.alpha(i).clamp(0.0, 1.0))
+    };
+
+    let image = match color_t {
+        ColorType::L8 => DynamicImage::ImageLuma8(ImageBuffer::from_fn(width, height, |x, y| {
+            Luma([to_u8(display(luminance(opaque(x, y))))])
+        })),
+        ColorType::L16 => DynamicImage::ImageLuma16(ImageBuffer::from_fn(width, height, |x, y| {
+            Luma([to_u16(display(luminance(opaque(x, y))))])
+        })),
+        ColorType::La8 => DynamicImage::ImageLumaA8(ImageBuffer::from_fn(width, height, |x, y| {
+            let (color, alpha) = straight(x, y);
+            LumaA([to_u8(display(luminance(color))), to_u8(alpha)])
+        })),
+        ColorType::La16 => DynamicImage::ImageLumaA16(ImageBuffer::from_fn(width, height, |x, y| {
+            let (color, alpha) = straight(x, y);
+            LumaA([to_u16(display(luminance(color))), to_u16(alpha)])
+        })),
+        ColorType::Rgb8 => DynamicImage::ImageRgb8(RgbImage::from_fn(width, height, |x, y| {
+            let color = opaque(x, y);
+            Rgb([to_u8(display(color.x)), to_u8(display(color.y)), to_u8(display(color.z))])
+        })),
+        ColorType::Rgb16 => DynamicImage::ImageRgb16(ImageBuffer::from_fn(width, height, |x, y| {
+            let color = opaque(x, y);
+            Rgb([to_u16(display(color.x)), to_u16(display(color.y)), to_u16(display(color.z))])
+        })),
+        ColorType::Rgba8 => DynamicImage::ImageRgba8(RgbaImage::from_fn(width, height, |x, y| {
+            let (color, alpha) = straight(x, y);
+            Rgba([to_u8(display(color.x)), to_u8(display(color.y)), to_u8(display(color.z)), to_u8(alpha)])
+        })),
+        ColorType::Rgba16 => DynamicImage::ImageRgba16(ImageBuffer::from_fn(width, height, |x, y| {
+            let (color, alpha) = straight(x, y);
+            Rgba([to_u16(display(color.x)), to_u16(display(color.y)), to_u16(display(color.z)), to_u16(alpha)])
+        })),
+        ColorType::Rgb32F => DynamicImage::ImageRgb32F(Rgb32FImage::from_fn(width, height, |x, y| {
+            let color = exposure_scale * opaque(x, y);
+            Rgb([color.x as f32, color.y as f32, color.z as f32])
+        })),
+        ColorType::Rgba32F => DynamicImage::ImageRgba32F(Rgba32FImage::from_fn(width, height, |x, y| {
+            let (color, alpha) = straight(x, y);
+            let color = exposure_scale * color;
+            Rgba([color.x as f32, color.y as f32, color.z as f32, alpha as f32])
+        })),
+        _ => {
+            return Err(format!("color_t {:?} is not supported", color_t));
+        }
+    };
+
+    return Ok(image);
+}
+
+/// Write image as PNG with colour space chunks.
+fn write_png(name: &str, image: &DynamicImage, transfer: TransferFunction) -> Result<(), String> {
+    // PNG stores 16-bit samples in big-endian.
+    let be_bytes = |samples: &[u16]| -> Vec<u8> {
+        samples.iter().flat_map(|c| c.to_be_bytes()).collect()
+    };
+
+    let (color, depth, data) = match image {
+        DynamicImage::ImageLuma8(buf) => (png::ColorType::Grayscale, png::BitDepth::Eight, buf.as_raw().clone()),
+        DynamicImage::ImageLumaA8(buf) => (png::ColorType::GrayscaleAlpha, png::BitDepth::Eight, buf.as_raw().clone()),
+        DynamicImage::ImageRgb8(buf) => (png::ColorType::Rgb, png::BitDepth::Eight, buf.as_raw().clone()),
+        DynamicImage::ImageRgba8(buf) => (png::ColorType::Rgba, png::BitDepth::Eight, buf.as_raw().clone()),
+        DynamicImage::ImageLuma16(buf) => (png::ColorType::Grayscale, png::BitDepth::Sixteen, be_bytes(buf.as_raw())),
+        DynamicImage::ImageLumaA16(buf) => (png::ColorType::GrayscaleAlpha, png::BitDepth::Sixteen, be_bytes(buf.as_raw())),
+        DynamicImage::ImageRgb16(buf) => (png::ColorType::Rgb, png::BitDepth::Sixteen, be_bytes(buf.as_raw())),
+        DynamicImage::ImageRgba16(buf) => (png::ColorType::Rgba, png::BitDepth::Sixteen, be_bytes(buf.as_raw())),
+        _ => return Err(format!("PNG does not support color_t {:?}", image.color()))
+    };
+
+    let is_gray = matches!(color, png::ColorType::Grayscale | png::ColorType::GrayscaleAlpha);
+
+    let file = File::create(name).map_err(|err| format!("{:?}", err))?;
+
+    let mut encoder = png::Encoder::new(BufWriter::new(file), image.width(), image.height());
+    encoder.set_color(color);
+    encoder.set_depth(depth);
+
+    if transfer == TransferFunction::Srgb {
+        encoder.set_srgb(png::SrgbRenderingIntent::Perceptual);
+    }
+    else {
+        encoder.set_source_gamma(png::ScaledFloat::new(transfer.png_gamma() as f32));
+
+        if !is_gray {
+            encoder.set_source_chromaticities(png::SourceChromaticities::new(
+                (0.3127, 0.3290), (0.64, 0.33), (0.30, 0.60), (0.15, 0.06)
+            ));
+        }
+    }
+
+    let mut writer = encoder.write_header().map_err(|err| format!("{:?}", err))?;
+
+    // `iCCP` of grayscale image requires a gray profile, so only color ones get it.
+    if transfer != TransferFunction::Srgb && !is_gray {
+        writer.write_chunk(png::chunk::ChunkType(*b"iCCP"), &iccp_chunk(transfer))
+              .map_err(|err| format!("{:?}", err))?;
+    }
+
+    writer.write_image_data(&data).map_err(|err| format!("{:?}", err))?;
+    writer.finish().map_err(|err| format!("{:?}", err))
+}
+
+/// Build `iCCP` chunk data: profile name, compression method and zlib-wrapped profile.
+fn iccp_chunk(transfer: TransferFunction) -> Vec<u8> {
+    let mut data = b"raytracing\0\0".to_vec();
+    data.extend(zlib_stored(&icc_profile(transfer)));
+    data
+}
+
+/// Build an ICC v2 display profile with BT.709 primaries and the given tone curve.
+fn icc_profile(transfer: TransferFunction) -> Vec<u8> {
+    let xyz = |x: f64, y: f64, z: f64| -> Vec<u8> {
+        let mut tag = b"XYZ \0\0\0\0".to_vec();
+        for v in [x, y, z] {
+            tag.extend(((v * 65536.0).round() as i32).to_be_bytes());
+        }
+        tag
+    };
+
+    let text = |content: &str| -> Vec<u8> {
+        let mut tag = b"text\0\0\0\0".to_vec();
+        tag.extend(content.as_bytes());
+        tag.push(0);
+        tag
+    };
 
-            for p in pixels {
-                buf.push((u8::MAX as f64 * intensity.clamp(p.x)) as u8);
-                buf.push((u8::MAX as f64 * intensity.clamp(p.y)) as u8);
-                buf.push((u8::MAX as f64 * intensity.clamp(p.z)) as u8);
+    let desc = |content: &str| -> Vec<u8> {
+        let mut tag = b"desc\0\0\0\0".to_vec();
+        tag.extend((content.len() as u32 + 1).to_be_bytes());
+        tag.extend(content.as_bytes());
+        tag.push(0);
+        // empty unicode and scriptcode descriptions.
+        tag.extend([0u8; 4 + 4 + 2 + 1 + 67]);
+        tag
+    };
+
+    let curve = {
+        let mut tag = b"curv\0\0\0\0".to_vec();
+        match transfer {
+            TransferFunction::Linear => tag.extend(0u32.to_be_bytes()),
+            TransferFunction::Gamma(gamma) => {
+                tag.extend(1u32.to_be_bytes());
+                tag.extend(((gamma * 256.0).round() as u16).to_be_bytes());
+            },
+            _ => {
+                let count = 1024;
+                tag.extend((count as u32).to_be_bytes());
+                for i in 0..count {
+                    let linear = transfer.decode(i as f64 / (count - 1) as f64);
+                    tag.extend(((linear * u16::MAX as f64).round() as u16).to_be_bytes());
+                }
             }
+        }
+        tag
+    };
+
+    let description = match transfer {
+        TransferFunction::Srgb => "sRGB".to_string(),
+        TransferFunction::Rec709 => "BT.709".to_string(),
+        TransferFunction::Gamma(gamma) => format!("BT.709 primaries, gamma {}", gamma),
+        TransferFunction::Linear => "Linear BT.709".to_string()
+    };
+
+    // Colorants of BT.709 primaries, chromatically adapted to D50.
+    let tags: Vec<(&[u8; 4], Vec<u8>)> = vec![
+        (b"desc", desc(&description)),
+        (b"cprt", text("No copyright, use freely")),
+        (b"wtpt", xyz(0.9642, 1.0, 0.8249)),
+        (b"rXYZ", xyz(0.4360747, 0.2225045, 0.0139322)),
+        (b"gXYZ", xyz(0.3850649, 0.7168786, 0.0971045)),
+        (b"bXYZ", xyz(0.1430804, 0.0606169, 0.7141733)),
+        (b"rTRC", curve.clone()),
+        (b"gTRC", curve.clone()),
+        (b"bTRC", curve)
+    ];
+
+    // Lay tag data out after header and tag table, each aligned to 4 bytes.
+    let mut table = (tags.len() as u32).to_be_bytes().to_vec();
+    let mut body = Vec::new();
+    let data_offset = 128 + 4 + 12 * tags.len();
+
+    for (signature, tag) in &tags {
+        table.extend(*signature);
+        table.extend(((data_offset + body.len()) as u32).to_be_bytes());
+        table.extend((tag.len() as u32).to_be_bytes());
+
+        body.extend(tag);
+        body.resize(body.len().next_multiple_of(4), 0);
+    }
+
+    let size = 128 + table.len() + body.len();
+
+    let mut header = vec![0u8; 128];
+    header[0..4].copy_from_slice(&(size as u32).to_be_bytes());
+    header[8..12].copy_from_slice(&[2, 0x10, 0, 0]);
+    header[12..16].copy_from_slice(b"mntr");
+    header[16..20].copy_from_slice(b"RGB ");
+    header[20..24].copy_from_slice(b"XYZ ");
+    header[36..40].copy_from_slice(b"acsp");
+    header[68..80].copy_from_slice(&xyz(0.9642, 1.0, 0.8249)[8..]);
+
+    let mut profile = header;
+    profile.extend(table);
+    profile.extend(body);
+    profile
+}
+
+/// Wrap data into a zlib stream made of *stored* (uncompressed) deflate blocks.
+fn zlib_stored(data: &[u8]) -> Vec<u8> {
+    let mut stream = vec![0x78, 0x01];
+
+    let mut chunks = data.chunks(u16::MAX as usize).peekable();
+    if chunks.peek().is_none() {
+        stream.extend([1, 0, 0, 0xFF, 0xFF]);
+    }
 
-            if let Err(err) = image::save_buffer(name, &buf, width, height, color_t) {
-                return Err(format!("{:?}", err));
+    while let Some(chunk) = chunks.next() {
+        stream.push(if chunks.peek().is_none() { 1 } else { 0 });
+        stream.extend((chunk.len() as u16).to_le_bytes());
+        stream.extend((!(chunk.len() as u16)).to_le_bytes());
+        stream.extend(chunk);
+    }
+
+    // Adler-32 checksum.
+    let (mut a, mut b) = (1u32, 0u32);
+    for byte in data {
+        a = (a + *byte as u32) % 65521;
+        b = (b + a) % 65521;
+    }
+    stream.extend(((b << 16) | a).to_be_bytes());
+
+    stream
+}
+
+#[cfg(test)]
+mod img_saver_tests {
+    use super::{ to_image, save_as, zlib_stored, PostConfig, ToneMapping, TransferFunction };
+    use crate::{ FrameBuffer, math::Vec3 };
+    use image::{ ColorType, DynamicImage };
+
+    const OPERATORS: [ToneMapping; 5] = [
+        ToneMapping::Clamp,
+        ToneMapping::Reinhard,
+        ToneMapping::ReinhardExt(4.0),
+        ToneMapping::Aces,
+        ToneMapping::Hable
+    ];
+
+    #[test]
+    fn tonemap_black() {
+        for op in OPERATORS {
+            assert!(op.apply(0.0).abs() < 1e-9, "{:?} does not keep black", op);
+            assert!(op.apply(-1.0).abs() < 1e-9, "{:?} does not clamp negative", op);
+        }
+    }
+
+    #[test]
+    fn tonemap_range() {
+        for op in OPERATORS {
+            let mut last = 0.0;
+            for i in 0..1000 {
+                let mapped = op.apply(i as f64 * 0.05);
+                assert!((0.0..=1.0).contains(&mapped), "{:?} is out of range", op);
+                assert!(mapped >= last, "{:?} is not monotonic", op);
+                last = mapped;
             }
-        },
-        ColorType::Rgb16 => {
-            let mut buf = Vec::with_capacity(pixels.len() * 3);
-
-            for p in pixels {
-                buf.push((u16::MAX as f64 * intensity.clamp(p.x)) as u16);
-                buf.push((u16::MAX as f64 * intensity.clamp(p.y)) as u16);
-                buf.push((u16::MAX as f64 * intensity.clamp(p.z)) as u16);
+        }
+    }
+
+    #[test]
+    fn tonemap_white_point() {
+        assert_eq!(ToneMapping::Clamp.apply(2.0), 1.0);
+        assert_eq!(ToneMapping::Reinhard.apply(1.0), 0.5);
+        assert!((ToneMapping::ReinhardExt(4.0).apply(4.0) - 1.0).abs() < 1e-9);
+        assert!((ToneMapping::Hable.apply(11.2 / 2.0) - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn transfer_roundtrip() {
+        let transfers = [
+            TransferFunction::Srgb,
+            TransferFunction::Rec709,
+            TransferFunction::Gamma(2.2),
+            TransferFunction::Linear
+        ];
+
+        for tf in transfers {
+            assert_eq!(tf.encode(0.0), 0.0);
+            assert!((tf.encode(1.0) - 1.0).abs() < 1e-3, "{:?} does not keep white", tf);
+
+            for i in 0..=100 {
+                let comp = i as f64 / 100.0;
+                assert!((tf.decode(tf.encode(comp)) - comp).abs() < 1e-3, "{:?} does not roundtrip", tf);
             }
+        }
+    }
+
+    #[test]
+    fn transfer_srgb_continuous() {
+        let srgb = TransferFunction::Srgb;
+        let below = srgb.encode(0.0031308 - 1e-9);
+        let above = srgb.encode(0.0031308 + 1e-9);
+
+        assert!((below - above).abs() < 1e-4);
+        assert!((srgb.encode(0.5) - 0.7354).abs() < 1e-4);
+    }
+
+    #[test]
+    fn png_chunks() {
+        let mut buffer = FrameBuffer::new((2, 1));
+        buffer.color_sum = vec![Vec3::new(1.0, 0.5, 0.0), Vec3::new(0.25, 0.1, 0.9)];
+        buffer.alpha_sum = vec![1.0, 1.0];
+        buffer.weight_sum = vec![1.0, 1.0];
+
+        let decode = |transfer: TransferFunction| -> (png::Info<'static>, Vec<u8>) {
+            let name = std::env::temp_dir().join(format!("raytracing_png_chunks_{:?}.png", transfer));
+            let name = name.to_str().unwrap();
+            let post = PostConfig { transfer, ..Default::default() };
+            save_as(name, &buffer, post, ColorType::Rgb8).unwrap();
+
+            let decoder = png::Decoder::new(std::fs::File::open(name).unwrap());
+            let mut reader = decoder.read_info().unwrap();
+            let mut pixels = vec![0; reader.output_buffer_size()];
+            reader.next_frame(&mut pixels).unwrap();
+            let info = reader.info().clone().to_owned();
+            std::fs::remove_file(name).unwrap();
+
+            let expected = to_image(&buffer, post, ColorType::Rgb8).unwrap();
+            assert_eq!(pixels, expected.as_bytes(), "{:?} does not roundtrip", transfer);
+            (info, pixels)
+        };
+
+        let (srgb, _) = decode(TransferFunction::Srgb);
+        assert!(srgb.srgb.is_some());
+        assert_eq!(srgb.gama_chunk.unwrap().into_scaled(), 45455);
+        assert!(srgb.icc_profile.is_none());
+
+        // gAMA of rec709 is the power curve fitting the whole piecewise curve, not its power segment.
+        let (rec709, _) = decode(TransferFunction::Rec709);
+        assert!(rec709.srgb.is_none());
+        let exponent = 100000.0 / rec709.gama_chunk.unwrap().into_scaled() as f64;
+        let error = |exponent: f64| (0..=255).map(|i| {
+            let signal = i as f64 / 255.0;
+            (TransferFunction::Rec709.decode(signal) - signal.powf(exponent)).powi(2)
+        }).sum::<f64>();
+        assert!(error(exponent) < error(1.0 / 0.45) && error(exponent) < error(2.0), "{}", exponent);
+        icc_roundtrip(&rec709.icc_profile.unwrap(), TransferFunction::Rec709);
+
+        let (gamma, pixels) = decode(TransferFunction::Gamma(2.0));
+        assert_eq!(gamma.gama_chunk.unwrap().into_scaled(), 50000);
+        icc_roundtrip(&gamma.icc_profile.unwrap(), TransferFunction::Gamma(2.0));
+        assert_eq!(&pixels[0..3], &[255, 180, 0]);
+
+        let (linear, _) = decode(TransferFunction::Linear);
+        icc_roundtrip(&linear.icc_profile.unwrap(), TransferFunction::Linear);
+    }
+
+    /// Check the layout of ICC profile decoded from `iCCP` chunk, and that its tone curve matches `transfer`.
+    fn icc_roundtrip(profile: &[u8], transfer: TransferFunction) {
+        let u32_at = |offset: usize| u32::from_be_bytes(profile[offset..offset + 4].try_into().unwrap()) as usize;
+        let u16_at = |offset: usize| u16::from_be_bytes(profile[offset..offset + 2].try_into().unwrap()) as f64;
 
-            let buf_ref = unsafe { 
-                let ptr = buf.as_ptr() as *const u16 as *const u8;
-                slice_from_raw_parts(ptr, buf.len() * 2).as_ref().unwrap()
-            };
+        assert_eq!(u32_at(0), profile.len());
+        assert_eq!(&profile[12..24], b"mntrRGB XYZ ");
+        assert_eq!(&profile[36..40], b"acsp");
 
-            if let Err(err) = image::save_buffer(name, buf_ref, width, height, color_t) {
-                return Err(format!("{:?}", err));
+        let count = u32_at(128);
+        let tags: Vec<(&[u8], usize, usize)> = (0..count).map(|i| {
+            let entry = 132 + 12 * i;
+            (&profile[entry..entry + 4], u32_at(entry + 4), u32_at(entry + 8))
+        }).collect();
+        for (_, offset, size) in &tags {
+            assert!(offset % 4 == 0 && offset + size <= profile.len());
+        }
+        for signature in [b"desc", b"wtpt", b"rXYZ", b"gXYZ", b"bXYZ", b"rTRC", b"gTRC", b"bTRC"] {
+            assert!(tags.iter().any(|(s, _, _)| s == signature), "{:?} misses tag", transfer);
+        }
+
+        // the red, green and blue colorants add up to the white point.
+        let xyz = |signature: &[u8]| -> Vec<f64> {
+            let (_, offset, _) = tags.iter().find(|(s, _, _)| *s == signature).unwrap();
+            (0..3).map(|i| u32_at(offset + 8 + 4 * i) as i32 as f64 / 65536.0).collect()
+        };
+        let (white, r, g, b) = (xyz(b"wtpt"), xyz(b"rXYZ"), xyz(b"gXYZ"), xyz(b"bXYZ"));
+        for c in 0..3 {
+            assert!((r[c] + g[c] + b[c] - white[c]).abs() < 1e-3);
+        }
+
+        let (_, offset, _) = tags.iter().find(|(s, _, _)| *s == b"rTRC").unwrap();
+        assert_eq!(&profile[*offset..offset + 4], b"curv");
+        let entries = u32_at(offset + 8);
+        let curve = |signal: f64| -> f64 {
+            match entries {
+                0 => signal,
+                1 => signal.powf(u16_at(offset + 12) / 256.0),
+                n => u16_at(offset + 12 + 2 * (signal * (n - 1) as f64).round() as usize) / u16::MAX as f64
             }
-        },
-        _ => {
-            return Err(format!("color_t {:?} is not supported", color_t));
+        };
+        // sample on the grid of 1024 entries, which tabulated curves use.
+        for i in (0..1024).step_by(31) {
+            let signal = i as f64 / 1023.0;
+            assert!((curve(signal) - transfer.decode(signal)).abs() < 1e-4, "{:?} curve mismatches", transfer);
         }
     }
 
-    return Ok(());
-}
\ No newline at end of file
+    #[test]
+    fn zlib_stream() {
+        // the Adler-32 checksum of "Wikipedia" is 0x11E60398.
+        let stream = zlib_stored(b"Wikipedia");
+        assert_eq!(&stream[..2], &[0x78, 0x01]);
+        assert_eq!(stream[2..7], [1, 9, 0, !9u8, 0xFF]);
+        assert_eq!(&stream[7..16], b"Wikipedia");
+        assert_eq!(&stream[16..], &[0x11, 0xE6, 0x03, 0x98]);
+
+        // long data is split into stored blocks, where only the last one is final.
+        let data = vec![7u8; 70000];
+        let stream = zlib_stored(&data);
+        assert_eq!(stream.len(), 2 + 2 * 5 + data.len() + 4);
+        assert_eq!(stream[2..7], [0, 0xFF, 0xFF, 0, 0]);
+        let second = 7 + u16::MAX as usize;
+        let rest = (data.len() - u16::MAX as usize) as u16;
+        assert_eq!(stream[second], 1);
+        assert_eq!(stream[second + 1..second + 3], rest.to_le_bytes());
+        assert_eq!(stream[second + 3..second + 5], (!rest).to_le_bytes());
+    }
+
+    #[test]
+    fn typed_buffers() {
+        let mut buffer = FrameBuffer::new((2, 1));
+        buffer.color_sum = vec![Vec3::new(1.0, 0.5, 0.0), Vec3::new(0.25, 0.25, 0.25)];
+        buffer.alpha_sum = vec![1.0, 0.5];
+        buffer.weight_sum = vec![1.0, 1.0];
+
+        let post = PostConfig { transfer: TransferFunction::Linear, ..Default::default() };
+
+        let DynamicImage::ImageRgb16(rgb16) = to_image(&buffer, post, ColorType::Rgb16).unwrap() else {
+            panic!("Rgb16 target does not produce ImageRgb16");
+        };
+        assert_eq!(rgb16.get_pixel(0, 0).0, [u16::MAX, 32768, 0]);
+        assert_eq!(rgb16.get_pixel(1, 0).0, [16384, 16384, 16384]);
+
+        let DynamicImage::ImageRgba8(rgba8) = to_image(&buffer, post, ColorType::Rgba8).unwrap() else {
+            panic!("Rgba8 target does not produce ImageRgba8");
+        };
+        assert_eq!(rgba8.get_pixel(1, 0).0, [128, 128, 128, 128]);
+
+        let DynamicImage::ImageLuma8(l8) = to_image(&buffer, post, ColorType::L8).unwrap() else {
+            panic!("L8 target does not produce ImageLuma8");
+        };
+        assert_eq!(l8.get_pixel(1, 0).0, [64]);
+
+        assert!(to_image(&buffer, post, ColorType::Rgb32F).is_ok());
+    }
+}